@@ -0,0 +1,7 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use rust_advent_of_code_2018::fuzz;
+
+fuzz_target!(|data: &[u8]| {
+  let _ = fuzz::guard_event(data);
+});
@@ -0,0 +1,12 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use rust_advent_of_code_2018::{fuzz, registry};
+
+// First byte picks one of the registered days, the rest is parsed as its input and both
+// of the parts are solved
+fuzz_target!(|data: &[u8]| {
+  if let Some((selector, input)) = data.split_first() {
+    let days = registry::days();
+    let _ = fuzz::solve(days[usize::from(*selector) % days.len()].day, input);
+  }
+});
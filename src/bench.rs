@@ -0,0 +1,203 @@
+use std::fs;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::registry::Day;
+use crate::solver::Context;
+use crate::utils::{read_input, read_toml};
+
+pub const DEFAULT_ITERATIONS: usize = 10;
+pub const DEFAULT_THRESHOLD: f64 = 10.0;
+
+/// Part of the work being measured, parsing includes reading the input file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stage {
+  Parse,
+  Part1,
+  Part2
+}
+
+impl Stage {
+  pub fn part(part: u8) -> Stage {
+    if part == 1 { Stage::Part1 } else { Stage::Part2 }
+  }
+
+  pub fn name(self) -> &'static str {
+    match self {
+      Stage::Parse => "parse",
+      Stage::Part1 => "part1",
+      Stage::Part2 => "part2"
+    }
+  }
+}
+
+/// Summary of the samples, all values are in nanoseconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+  pub min: f64,
+  pub median: f64,
+  pub mean: f64,
+  pub stddev: f64
+}
+
+impl Stats {
+  pub fn from_samples(samples: &[Duration]) -> Stats {
+    let mut nanos: Vec<f64> = samples.iter().map(|d| d.as_nanos() as f64).collect();
+    nanos.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let count = nanos.len().max(1) as f64;
+    let mean = nanos.iter().sum::<f64>() / count;
+    let variance = nanos.iter().map(|n| (n - mean).powi(2)).sum::<f64>() / count;
+    let median = match nanos.len() {
+      0 => 0.0,
+      l if l % 2 == 0 => (nanos[l / 2 - 1] + nanos[l / 2]) / 2.0,
+      l => nanos[l / 2]
+    };
+
+    Stats {
+      min: nanos.first().cloned().unwrap_or(0.0),
+      median,
+      mean,
+      stddev: variance.sqrt()
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Measurement {
+  pub day: u8,
+  pub stage: Stage,
+  #[serde(flatten)]
+  pub stats: Stats
+}
+
+fn time<T, F: FnMut() -> Result<T>>(iterations: usize, mut f: F) -> Result<Vec<Duration>> {
+  (0 .. iterations).map(|_| {
+    let start = Instant::now();
+    f()?;
+    Ok(start.elapsed())
+  }).collect()
+}
+
+/// Runs parsing and every selected part of the day `iterations` times
+pub fn bench_day(day: &Day, path: &str, parts: &[u8], iterations: usize, ctx: &Context) -> Result<Vec<Measurement>> {
+  let parse_samples = time(iterations, || {
+    read_input(path).and_then(|input| day.solver.parse_input(&input))
+  }).map_err(|e| e.in_file(path))?;
+  let mut measurements = vec![
+    Measurement { day: day.day, stage: Stage::Parse, stats: Stats::from_samples(&parse_samples) }
+  ];
+
+  let input = day.load(path)?;
+  for part in parts {
+    let samples = time(iterations, || day.solver.solve(*part, &*input, ctx))?;
+    measurements.push(Measurement { day: day.day, stage: Stage::part(*part), stats: Stats::from_samples(&samples) });
+  }
+
+  Ok(measurements)
+}
+
+/// Saved measurements a later run can be compared against
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Baseline {
+  #[serde(default)]
+  pub measurement: Vec<Measurement>
+}
+
+impl Baseline {
+  pub fn load(path: &str) -> Result<Baseline> {
+    read_toml(path)
+  }
+
+  pub fn save(measurements: &[Measurement], path: &str) -> Result<()> {
+    let baseline = Baseline { measurement: measurements.to_vec() };
+    let content = toml::to_string(&baseline)
+      .map_err(|e| Error::InvalidInput(format!("Unable to serialize baseline: {}", e)))?;
+
+    fs::write(path, content).map_err(|source| Error::Io { path: path.to_string(), source })
+  }
+
+  pub fn find(&self, day: u8, stage: Stage) -> Option<&Measurement> {
+    self.measurement.iter().find(|m| m.day == day && m.stage == stage)
+  }
+}
+
+/// Change of the median compared to the baseline, in percent
+pub fn change(current: &Measurement, baseline: &Baseline) -> Option<f64> {
+  baseline.find(current.day, current.stage)
+    .filter(|b| b.stats.median > 0.0)
+    .map(|b| (current.stats.median - b.stats.median) / b.stats.median * 100.0)
+}
+
+fn format_nanos(nanos: f64) -> String {
+  if nanos >= 1e9 {
+    format!("{:.2}s", nanos / 1e9)
+  } else if nanos >= 1e6 {
+    format!("{:.2}ms", nanos / 1e6)
+  } else if nanos >= 1e3 {
+    format!("{:.2}us", nanos / 1e3)
+  } else {
+    format!("{:.0}ns", nanos)
+  }
+}
+
+/// Renders measurements as a plain text table, with changes against the baseline when
+/// one is given. Median slower than `threshold` percent is flagged as a regression
+pub fn render_table(measurements: &[Measurement], baseline: Option<&Baseline>, threshold: f64) -> String {
+  let mut table = format!("{:>3}  {:<6}  {:>10}  {:>10}  {:>10}  {:>10}  {}\n", "Day", "Stage", "min", "median", "mean", "stddev", "change");
+
+  for m in measurements {
+    let change = match baseline.and_then(|b| change(m, b)) {
+      Some(c) if c > threshold => format!("{:+.1}% REGRESSION", c),
+      Some(c) => format!("{:+.1}%", c),
+      None => String::new()
+    };
+
+    table += &format!(
+      "{:>3}  {:<6}  {:>10}  {:>10}  {:>10}  {:>10}  {}\n",
+      m.day, m.stage.name(),
+      format_nanos(m.stats.min), format_nanos(m.stats.median), format_nanos(m.stats.mean), format_nanos(m.stats.stddev),
+      change
+    );
+  }
+
+  table
+}
+
+/// Measurements with median slower than `threshold` percent compared to the baseline
+pub fn regressions<'a>(measurements: &'a [Measurement], baseline: &Baseline, threshold: f64) -> Vec<&'a Measurement> {
+  measurements.iter()
+    .filter(|m| change(m, baseline).is_some_and(|c| c > threshold))
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn measurement(stage: Stage, median: f64) -> Measurement {
+    Measurement { day: 1, stage, stats: Stats { min: median, median, mean: median, stddev: 0.0 } }
+  }
+
+  #[test]
+  fn stats_are_calculated_from_samples() {
+    let samples: Vec<Duration> = [4, 1, 3, 2].iter().map(|n| Duration::from_nanos(*n)).collect();
+    let stats = Stats::from_samples(&samples);
+
+    assert_eq!(stats.min, 1.0);
+    assert_eq!(stats.median, 2.5);
+    assert_eq!(stats.mean, 2.5);
+    assert!((stats.stddev - 1.118).abs() < 0.001);
+  }
+
+  #[test]
+  fn regressions_are_flagged_above_threshold() {
+    let baseline = Baseline { measurement: vec![measurement(Stage::Parse, 100.0), measurement(Stage::Part1, 100.0)] };
+    let current = vec![measurement(Stage::Parse, 105.0), measurement(Stage::Part1, 120.0), measurement(Stage::Part2, 500.0)];
+
+    let flagged: Vec<Stage> = regressions(&current, &baseline, 10.0).iter().map(|m| m.stage).collect();
+    assert_eq!(flagged, vec![Stage::Part1]);
+  }
+}
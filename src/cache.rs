@@ -0,0 +1,95 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::solver::{Answer, Params};
+use crate::utils::read_toml;
+
+pub const DEFAULT_CACHE_PATH: &str = ".cache/answers.toml";
+
+/// Answers computed by earlier runs. An answer is only reused for the same day and part,
+/// the same input content, the same solver version, the same strategy and the same
+/// puzzle parameters
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AnswerCache {
+  #[serde(default)]
+  answers: BTreeMap<String, Answer>
+}
+
+impl AnswerCache {
+  /// Loads the cache, a missing file is an empty cache
+  pub fn load(path: &str) -> Result<AnswerCache> {
+    if Path::new(path).exists() { read_toml(path) } else { Ok(AnswerCache::default()) }
+  }
+
+  pub fn save(&self, path: &str) -> Result<()> {
+    let io_error = |source| Error::Io { path: path.to_string(), source };
+    let content = toml::to_string(self)
+      .map_err(|e| Error::InvalidInput(format!("Unable to serialize cache: {}", e)))?;
+
+    if let Some(parent) = Path::new(path).parent() {
+      fs::create_dir_all(parent).map_err(io_error)?;
+    }
+    fs::write(path, content).map_err(io_error)
+  }
+
+  /// Removes the cache file, returns false if there was none
+  pub fn clean(path: &str) -> Result<bool> {
+    match fs::remove_file(path) {
+      Ok(()) => Ok(true),
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+      Err(source) => Err(Error::Io { path: path.to_string(), source })
+    }
+  }
+
+  /// Key of an answer, `strategy` is the one the part was solved with, `None` for the default
+  pub fn key(day: u8, part: u8, input_hash: &str, version: &str, strategy: Option<&str>, params: &Params) -> String {
+    let params = serde_json::to_string(params).unwrap_or_default();
+
+    format!("day{}/part{}/{}/{}/{}/{}", day, part, input_hash, version, strategy.unwrap_or("default"), params)
+  }
+
+  pub fn get(&self, key: &str) -> Option<&Answer> {
+    self.answers.get(key)
+  }
+
+  pub fn insert(&mut self, key: String, answer: Answer) {
+    self.answers.insert(key, answer);
+  }
+
+  pub fn len(&self) -> usize {
+    self.answers.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.answers.is_empty()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn cache_survives_saving_and_cleaning() {
+    let path = std::env::temp_dir().join(format!("aoc-cache-{}/answers.toml", std::process::id()));
+    let path = path.to_string_lossy().to_string();
+    let mut params = Params::new();
+    params.set("workers", 2);
+    let key = AnswerCache::key(7, 2, "00ff", "1", None, &params);
+
+    let mut cache = AnswerCache::default();
+    cache.insert(key.clone(), Answer::Number(15));
+    cache.save(&path).unwrap();
+
+    let loaded = AnswerCache::load(&path).unwrap();
+    assert_eq!(loaded.get(&key), Some(&Answer::Number(15)));
+    assert_eq!(loaded.get(&AnswerCache::key(7, 2, "00ff", "1", None, &Params::new())), None);
+    assert_eq!(loaded.get(&AnswerCache::key(7, 2, "00ff", "1", Some("grid"), &params)), None);
+    assert_eq!(loaded.get(&AnswerCache::key(7, 2, "00ff", "2", None, &params)), None);
+    assert!(AnswerCache::clean(&path).unwrap());
+    assert!(!AnswerCache::clean(&path).unwrap());
+  }
+}
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::error::{Error, Result};
+
+/// Token the runner cancels when a part runs out of its time budget. Solvers check it in
+/// their long loops and stop with `Error::Cancelled`, the runner can't stop them otherwise
+#[derive(Debug, Clone, Default)]
+pub struct Cancellation {
+  cancelled: Arc<AtomicBool>
+}
+
+impl Cancellation {
+  pub fn new() -> Cancellation {
+    Cancellation::default()
+  }
+
+  /// Cancels every clone of the token
+  pub fn cancel(&self) {
+    self.cancelled.store(true, Ordering::Relaxed);
+  }
+
+  pub fn is_cancelled(&self) -> bool {
+    self.cancelled.load(Ordering::Relaxed)
+  }
+
+  /// Fails once the token is cancelled, so loops can stop with `?`
+  pub fn check(&self) -> Result<()> {
+    if self.is_cancelled() {
+      Err(Error::Cancelled)
+    } else {
+      Ok(())
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn clones_share_cancellation() {
+    let token = Cancellation::new();
+    let clone = token.clone();
+    assert!(clone.check().is_ok());
+
+    token.cancel();
+    assert!(matches!(clone.check(), Err(Error::Cancelled)));
+  }
+}
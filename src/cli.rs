@@ -0,0 +1,770 @@
+use std::fmt;
+
+use rust_advent_of_code_2018::report::Format;
+use rust_advent_of_code_2018::solver::Answer;
+
+pub const USAGE: &str = "Usage: rust-advent-of-code-2018 [COMMAND] [OPTIONS]
+
+Commands:
+  run          Solves the selected days, the default without any arguments
+  verify       Checks the solvers against the confirmed answers
+  bench        Measures how long parsing and solving take
+  fetch        Downloads puzzle inputs
+  submit       Submits an answer and records the verdict
+  new-day      Generates and registers the module of a new day
+  watch        Runs a day again whenever its data changes
+  compare      Solves the parts with each of their strategies
+  gen          Generates a random input with known answers
+  cache-clean  Removes the answer cache
+  help         Prints this help, or the help of a command
+
+Run help <COMMAND> for the options of a command.";
+
+const CONFIG_OPTIONS: &str = "Config options:
+  --config <PATH>        Project configuration, aoc.toml by default
+  --data-dir <DIR>       Directory with the dayN input directories
+  --param <NAME=VALUE>   Puzzle parameter of the selected days, can be repeated
+  --strategy <NAME>      Strategy to solve the parts with, parts without it use the default
+  --timeout <MS>         Time budget of each part solved by run, watch or compare
+
+Data directory, per-day input files and puzzle parameters (like workers and
+offset of day 7, or players and last_marble of day 9) are read from aoc.toml
+when it exists. Command line options take precedence over it.";
+
+const RUN_HELP: &str = "Usage: rust-advent-of-code-2018 run <DAY|all> [--part <1|2>] [--input <PATH>]
+         [--format <text|json|markdown>] [--jobs <N>] [--no-cache] [--explain] [--memory]
+         [--isolate [--cpu-limit <SECS>] [--memory-limit <MIB>]] [CONFIG OPTIONS]
+
+Runs the solvers of selected day, or all the days linked into the binary.
+Without any arguments all the days are run. Days and parts are solved in
+parallel, on one thread per CPU unless --jobs is given, and printed in order.
+An --input of - reads the puzzle input from the standard input. Results can be
+printed as plain text (default), a JSON document or a Markdown table.
+
+Answers are cached in .cache/answers.toml, keyed by the day, part, input
+content, solver version, strategy and parameters. Cached answers are marked as
+such, --no-cache solves everything again and cache-clean removes the cache.
+
+--explain solves the parts without the cache and prints the steps the solvers
+took to the standard error, indented text or JSON lines with --format json.
+
+--memory reports the peak bytes, allocations and bytes left live of every
+part. It needs a build with the memory feature, which installs a counting
+allocator, and solves the parts one at a time without the cache.
+
+Parts running longer than their time budget, set by timeout_ms in aoc.toml or
+--timeout, are cancelled and reported as timed out, the other parts still run.
+Solvers stop once they notice the cancellation, one that never checks for it is
+reported as timed out when it finishes.
+
+--isolate solves every part in its own child process, limited to 60 seconds of
+CPU time and 4096 MiB of address space unless --cpu-limit or --memory-limit is
+given. A part that crashes, runs out of memory or is killed by a limit fails
+without taking the other parts down. Children are started with the internal
+isolated command, which reads the part from its standard input. Isolated parts
+are always solved again, their answers are still cached.";
+
+const VERIFY_HELP: &str = "Usage: rust-advent-of-code-2018 verify [--answers <PATH>]
+
+Checks every registered solver against the confirmed answers, read from
+answers.toml by default.";
+
+const BENCH_HELP: &str = "Usage: rust-advent-of-code-2018 bench <DAY|all> [--part <1|2>] [--iterations <N>]
+         [--save <PATH>] [--baseline <PATH>] [--threshold <PERCENT>] [CONFIG OPTIONS]
+
+Runs parsing and solving of each selected part many times and reports
+min/median/mean/stddev. Measurements can be saved as a baseline, and a later
+run compared against it flags medians slower by more than the threshold
+(10% by default). Parts are solved without a time budget, so --timeout is
+rejected.";
+
+const FETCH_HELP: &str = "Usage: rust-advent-of-code-2018 fetch <DAY|all> [CONFIG OPTIONS]
+
+Downloads puzzle inputs from base_url of the config into the data directory,
+using the session token from the AOC_SESSION environment variable or session
+of the config. Inputs already on disk are never downloaded again.";
+
+const SUBMIT_HELP: &str = "Usage: rust-advent-of-code-2018 submit <DAY> <1|2> [ANSWER] [--input <PATH>] [--ledger <PATH>]
+         [CONFIG OPTIONS]
+
+Posts the answer, or the one the solver gives when none is given, and records
+the verdict in ledger.toml. Answers already rejected, or outside the bounds set
+by earlier too high and too low answers, are never submitted.";
+
+const NEW_DAY_HELP: &str = "Usage: rust-advent-of-code-2018 new-day <DAY>
+
+Generates src/dayN.rs with solver stubs and a test module, creates data/dayN
+with an example placeholder, and registers the day in Cargo.toml, lib.rs and
+the registry.";
+
+const WATCH_HELP: &str = "Usage: rust-advent-of-code-2018 watch <DAY> [--interval <MS>] [CONFIG OPTIONS]
+
+Runs the day and its examples, then polls data/dayN and the input every 500ms
+by default and runs them again on every change, showing which answers changed.
+The binary can't reload its own code, run it under cargo-watch to pick up
+changes of the solver too.";
+
+const COMPARE_HELP: &str = "Usage: rust-advent-of-code-2018 compare <DAY|all> [--part <1|2>] [--input <PATH>] [CONFIG OPTIONS]
+
+Some parts have several strategies, like vec and linked-list of day 9 part 1
+or grid and overlap of day 3. compare solves the parts with each of them, checks they
+agree and reports how long each took.";
+
+const GEN_HELP: &str = "Usage: rust-advent-of-code-2018 gen <DAY> [--seed <S>] [--size <K>] [--output <PATH>]
+
+Writes a random input of the day, the same one for the same seed, to the
+standard output or --output. Size is the number of lines, claims, shifts,
+polymer units, steps or tree nodes, or the last marble of day 9. Answers known
+from the way the input is built are printed to the standard error, or written
+to an .expected.toml sidecar next to the output, so inputs generated into
+data/dayN are checked with the other examples.";
+
+const CACHE_CLEAN_HELP: &str = "Usage: rust-advent-of-code-2018 cache-clean
+
+Removes the answer cache, .cache/answers.toml.";
+
+/// Help of a single command, followed by the config options when it takes them
+pub fn command_help(command: &str) -> Option<String> {
+  let (help, config) = match command {
+    "run" => (RUN_HELP, true),
+    "verify" => (VERIFY_HELP, false),
+    "bench" => (BENCH_HELP, true),
+    "fetch" => (FETCH_HELP, true),
+    "submit" => (SUBMIT_HELP, true),
+    "new-day" => (NEW_DAY_HELP, false),
+    "watch" => (WATCH_HELP, true),
+    "compare" => (COMPARE_HELP, true),
+    "gen" => (GEN_HELP, false),
+    "cache-clean" => (CACHE_CLEAN_HELP, false),
+    _ => return None
+  };
+
+  Some(if config { format!("{}\n\n{}", help, CONFIG_OPTIONS) } else { help.to_string() })
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DaySelection {
+  All,
+  Day(u8)
+}
+
+/// Command line options applied on top of the project configuration
+#[derive(Debug, Default, PartialEq)]
+pub struct Overrides {
+  pub config: Option<String>,
+  pub data_dir: Option<String>,
+  pub params: Vec<(String, i64)>,
+  pub strategy: Option<String>,
+  pub timeout_ms: Option<u64>
+}
+
+/// How the parts selected by `run` are solved and reported
+#[derive(Debug, PartialEq)]
+pub struct RunOptions {
+  pub format: Format,
+  pub jobs: Option<usize>,
+  pub no_cache: bool,
+  pub explain: bool,
+  pub memory: bool,
+  pub isolate: bool,
+  pub cpu_limit: Option<u64>,
+  pub memory_limit: Option<u64>
+}
+
+impl Default for RunOptions {
+  fn default() -> RunOptions {
+    RunOptions {
+      format: Format::Text, jobs: None, no_cache: false, explain: false, memory: false,
+      isolate: false, cpu_limit: None, memory_limit: None
+    }
+  }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+  Run {
+    days: DaySelection,
+    part: Option<u8>,
+    input: Option<String>,
+    options: RunOptions,
+    overrides: Overrides
+  },
+  Verify {
+    answers: Option<String>
+  },
+  Fetch {
+    days: DaySelection,
+    overrides: Overrides
+  },
+  Submit {
+    day: u8,
+    part: u8,
+    answer: Option<Answer>,
+    input: Option<String>,
+    ledger: Option<String>,
+    overrides: Overrides
+  },
+  Bench {
+    days: DaySelection,
+    part: Option<u8>,
+    iterations: Option<usize>,
+    save: Option<String>,
+    baseline: Option<String>,
+    threshold: Option<f64>,
+    overrides: Overrides
+  },
+  NewDay {
+    day: u8
+  },
+  Watch {
+    day: u8,
+    interval: Option<u64>,
+    overrides: Overrides
+  },
+  CacheClean,
+  /// Child side of `run --isolate`
+  Isolated,
+  Compare {
+    days: DaySelection,
+    part: Option<u8>,
+    input: Option<String>,
+    overrides: Overrides
+  },
+  Gen {
+    day: u8,
+    seed: Option<u64>,
+    size: Option<usize>,
+    output: Option<String>
+  },
+  /// Overview of the commands, or the help of the given one
+  Help(Option<String>)
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CliError {
+  UnknownCommand(String),
+  UnexpectedArgument(String),
+  MissingValue(&'static str),
+  InvalidValue(&'static str, String),
+  UnknownDay(u8, Vec<u8>)
+}
+
+impl fmt::Display for CliError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      CliError::UnknownCommand(command) => write!(f, "Unknown command: {}", command),
+      CliError::UnexpectedArgument(arg) => write!(f, "Unexpected argument: {}", arg),
+      CliError::MissingValue(name) => write!(f, "Missing value for {}", name),
+      CliError::InvalidValue(name, value) => write!(f, "Invalid value for {}: {}", name, value),
+      CliError::UnknownDay(day, available) => write!(
+        f, "Day {} is not available, available days: {}",
+        day,
+        available.iter().map(|d| d.to_string()).collect::<Vec<String>>().join(", ")
+      )
+    }
+  }
+}
+
+fn parse_day(value: &str) -> Result<DaySelection, CliError> {
+  if value == "all" {
+    return Ok(DaySelection::All);
+  }
+
+  value.parse::<u8>()
+    .map(DaySelection::Day)
+    .map_err(|_| CliError::InvalidValue("DAY", value.to_string()))
+}
+
+fn parse_part(value: &str) -> Result<u8, CliError> {
+  match value {
+    "1" => Ok(1),
+    "2" => Ok(2),
+    _ => Err(CliError::InvalidValue("--part", value.to_string()))
+  }
+}
+
+fn parse_param(value: Option<String>) -> Result<(String, i64), CliError> {
+  let value = value.ok_or(CliError::MissingValue("--param"))?;
+
+  match value.split_once('=') {
+    Some((name, number)) if !name.is_empty() => number.parse::<i64>()
+      .map(|number| (name.to_string(), number))
+      .map_err(|_| CliError::InvalidValue("--param", value.clone())),
+    _ => Err(CliError::InvalidValue("--param", value))
+  }
+}
+
+/// Reads a config option into the overrides, returns false if the argument isn't one
+fn parse_override<I: Iterator<Item = String>>(arg: &str, args: &mut I, overrides: &mut Overrides) -> Result<bool, CliError> {
+  match arg {
+    "--config" => {
+      overrides.config = Some(args.next().ok_or(CliError::MissingValue("--config"))?);
+    },
+    "--data-dir" => {
+      overrides.data_dir = Some(args.next().ok_or(CliError::MissingValue("--data-dir"))?);
+    },
+    "--param" => {
+      overrides.params.push(parse_param(args.next())?);
+    },
+    "--strategy" => {
+      overrides.strategy = Some(args.next().ok_or(CliError::MissingValue("--strategy"))?);
+    },
+    "--timeout" => {
+      overrides.timeout_ms = Some(parse_number("--timeout", args.next())?);
+    },
+    _ => return Ok(false)
+  }
+
+  Ok(true)
+}
+
+fn parse_run<I: Iterator<Item = String>>(mut args: I) -> Result<Command, CliError> {
+  let days = parse_day(&args.next().ok_or(CliError::MissingValue("DAY"))?)?;
+  let mut part: Option<u8> = None;
+  let mut input: Option<String> = None;
+  let mut options = RunOptions::default();
+  let mut overrides = Overrides::default();
+
+  while let Some(arg) = args.next() {
+    if parse_override(&arg, &mut args, &mut overrides)? {
+      continue;
+    }
+
+    match arg.as_str() {
+      "--part" => {
+        part = Some(parse_part(&args.next().ok_or(CliError::MissingValue("--part"))?)?);
+      },
+      "--input" => {
+        input = Some(args.next().ok_or(CliError::MissingValue("--input"))?);
+      },
+      "--format" => {
+        let name = args.next().ok_or(CliError::MissingValue("--format"))?;
+        options.format = Format::from_name(&name).ok_or(CliError::InvalidValue("--format", name))?;
+      },
+      "--jobs" => {
+        options.jobs = Some(parse_number("--jobs", args.next())?);
+      },
+      "--no-cache" => options.no_cache = true,
+      "--explain" => options.explain = true,
+      "--memory" => options.memory = true,
+      "--isolate" => options.isolate = true,
+      "--cpu-limit" => {
+        options.cpu_limit = Some(parse_number("--cpu-limit", args.next())?);
+      },
+      "--memory-limit" => {
+        options.memory_limit = Some(parse_number("--memory-limit", args.next())?);
+      },
+      _ => return Err(CliError::UnexpectedArgument(arg))
+    }
+  }
+
+  if input.is_some() && days == DaySelection::All {
+    return Err(CliError::InvalidValue("--input", "cannot be used when running all days".to_string()));
+  }
+
+  if !options.isolate && (options.cpu_limit.is_some() || options.memory_limit.is_some()) {
+    return Err(CliError::InvalidValue("--cpu-limit", "limits can only be used with --isolate".to_string()));
+  }
+
+  // Children only report the answer, and the standard input carries the part they solve
+  if options.isolate && (options.explain || options.memory || input.as_deref() == Some("-")) {
+    return Err(CliError::InvalidValue("--isolate", "cannot be used with --explain, --memory or --input -".to_string()));
+  }
+
+  if options.jobs == Some(0) {
+    return Err(CliError::InvalidValue("--jobs", "0".to_string()));
+  }
+
+  Ok(Command::Run { days, part, input, options, overrides })
+}
+
+fn parse_number<T: std::str::FromStr>(name: &'static str, value: Option<String>) -> Result<T, CliError> {
+  let value = value.ok_or(CliError::MissingValue(name))?;
+  value.parse::<T>().map_err(|_| CliError::InvalidValue(name, value))
+}
+
+fn parse_bench<I: Iterator<Item = String>>(mut args: I) -> Result<Command, CliError> {
+  let days = parse_day(&args.next().ok_or(CliError::MissingValue("DAY"))?)?;
+  let mut part: Option<u8> = None;
+  let mut iterations: Option<usize> = None;
+  let mut save: Option<String> = None;
+  let mut baseline: Option<String> = None;
+  let mut threshold: Option<f64> = None;
+  let mut overrides = Overrides::default();
+
+  while let Some(arg) = args.next() {
+    if parse_override(&arg, &mut args, &mut overrides)? {
+      continue;
+    }
+
+    match arg.as_str() {
+      "--part" => {
+        part = Some(parse_part(&args.next().ok_or(CliError::MissingValue("--part"))?)?);
+      },
+      "--iterations" => {
+        iterations = Some(parse_number("--iterations", args.next())?);
+      },
+      "--save" => {
+        save = Some(args.next().ok_or(CliError::MissingValue("--save"))?);
+      },
+      "--baseline" => {
+        baseline = Some(args.next().ok_or(CliError::MissingValue("--baseline"))?);
+      },
+      "--threshold" => {
+        threshold = Some(parse_number("--threshold", args.next())?);
+      },
+      _ => return Err(CliError::UnexpectedArgument(arg))
+    }
+  }
+
+  if iterations == Some(0) {
+    return Err(CliError::InvalidValue("--iterations", "0".to_string()));
+  }
+  if overrides.timeout_ms.is_some() {
+    return Err(CliError::InvalidValue("--timeout", "cannot be used with bench".to_string()));
+  }
+
+  Ok(Command::Bench { days, part, iterations, save, baseline, threshold, overrides })
+}
+
+fn parse_fetch<I: Iterator<Item = String>>(mut args: I) -> Result<Command, CliError> {
+  let days = parse_day(&args.next().ok_or(CliError::MissingValue("DAY"))?)?;
+  let mut overrides = Overrides::default();
+
+  while let Some(arg) = args.next() {
+    if !parse_override(&arg, &mut args, &mut overrides)? {
+      return Err(CliError::UnexpectedArgument(arg));
+    }
+  }
+
+  Ok(Command::Fetch { days, overrides })
+}
+
+/// Answers that look like numbers are submitted as numbers, anything else as text
+fn parse_answer(value: String) -> Answer {
+  value.parse::<i64>().map_or_else(|_| Answer::Text(value), Answer::Number)
+}
+
+fn parse_submit<I: Iterator<Item = String>>(mut args: I) -> Result<Command, CliError> {
+  let day = parse_number("DAY", args.next())?;
+  let part = parse_part(&args.next().ok_or(CliError::MissingValue("PART"))?)?;
+  let mut answer: Option<Answer> = None;
+  let mut input: Option<String> = None;
+  let mut ledger: Option<String> = None;
+  let mut overrides = Overrides::default();
+
+  while let Some(arg) = args.next() {
+    if parse_override(&arg, &mut args, &mut overrides)? {
+      continue;
+    }
+
+    match arg.as_str() {
+      "--input" => {
+        input = Some(args.next().ok_or(CliError::MissingValue("--input"))?);
+      },
+      "--ledger" => {
+        ledger = Some(args.next().ok_or(CliError::MissingValue("--ledger"))?);
+      },
+      _ if answer.is_none() && !arg.starts_with("--") => answer = Some(parse_answer(arg)),
+      _ => return Err(CliError::UnexpectedArgument(arg))
+    }
+  }
+
+  Ok(Command::Submit { day, part, answer, input, ledger, overrides })
+}
+
+fn parse_new_day<I: Iterator<Item = String>>(mut args: I) -> Result<Command, CliError> {
+  let day = parse_number("DAY", args.next())?;
+
+  match args.next() {
+    Some(arg) => Err(CliError::UnexpectedArgument(arg)),
+    None => Ok(Command::NewDay { day })
+  }
+}
+
+fn parse_watch<I: Iterator<Item = String>>(mut args: I) -> Result<Command, CliError> {
+  let day = parse_number("DAY", args.next())?;
+  let mut interval: Option<u64> = None;
+  let mut overrides = Overrides::default();
+
+  while let Some(arg) = args.next() {
+    if parse_override(&arg, &mut args, &mut overrides)? {
+      continue;
+    }
+
+    match arg.as_str() {
+      "--interval" => {
+        interval = Some(parse_number("--interval", args.next())?);
+      },
+      _ => return Err(CliError::UnexpectedArgument(arg))
+    }
+  }
+
+  if interval == Some(0) {
+    return Err(CliError::InvalidValue("--interval", "0".to_string()));
+  }
+
+  Ok(Command::Watch { day, interval, overrides })
+}
+
+fn parse_compare<I: Iterator<Item = String>>(mut args: I) -> Result<Command, CliError> {
+  let days = parse_day(&args.next().ok_or(CliError::MissingValue("DAY"))?)?;
+  let mut part: Option<u8> = None;
+  let mut input: Option<String> = None;
+  let mut overrides = Overrides::default();
+
+  while let Some(arg) = args.next() {
+    if parse_override(&arg, &mut args, &mut overrides)? {
+      continue;
+    }
+
+    match arg.as_str() {
+      "--part" => {
+        part = Some(parse_part(&args.next().ok_or(CliError::MissingValue("--part"))?)?);
+      },
+      "--input" => {
+        input = Some(args.next().ok_or(CliError::MissingValue("--input"))?);
+      },
+      _ => return Err(CliError::UnexpectedArgument(arg))
+    }
+  }
+
+  if input.is_some() && days == DaySelection::All {
+    return Err(CliError::InvalidValue("--input", "cannot be used when comparing all days".to_string()));
+  }
+
+  Ok(Command::Compare { days, part, input, overrides })
+}
+
+fn parse_gen<I: Iterator<Item = String>>(mut args: I) -> Result<Command, CliError> {
+  let day = parse_number("DAY", args.next())?;
+  let mut seed: Option<u64> = None;
+  let mut size: Option<usize> = None;
+  let mut output: Option<String> = None;
+
+  while let Some(arg) = args.next() {
+    match arg.as_str() {
+      "--seed" => {
+        seed = Some(parse_number("--seed", args.next())?);
+      },
+      "--size" => {
+        size = Some(parse_number("--size", args.next())?);
+      },
+      "--output" => {
+        output = Some(args.next().ok_or(CliError::MissingValue("--output"))?);
+      },
+      _ => return Err(CliError::UnexpectedArgument(arg))
+    }
+  }
+
+  Ok(Command::Gen { day, seed, size, output })
+}
+
+fn parse_verify<I: Iterator<Item = String>>(mut args: I) -> Result<Command, CliError> {
+  let mut answers: Option<String> = None;
+
+  while let Some(arg) = args.next() {
+    match arg.as_str() {
+      "--answers" => {
+        answers = Some(args.next().ok_or(CliError::MissingValue("--answers"))?);
+      },
+      _ => return Err(CliError::UnexpectedArgument(arg))
+    }
+  }
+
+  Ok(Command::Verify { answers })
+}
+
+/// Parses command line arguments, excluding the program name
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, CliError> {
+  let mut args = args.into_iter();
+
+  match args.next().as_deref() {
+    None => Ok(Command::Run {
+      days: DaySelection::All, part: None, input: None, options: RunOptions::default(), overrides: Overrides::default()
+    }),
+    Some("run") => parse_run(args),
+    Some("verify") => parse_verify(args),
+    Some("fetch") => parse_fetch(args),
+    Some("submit") => parse_submit(args),
+    Some("new-day") => parse_new_day(args),
+    Some("watch") => parse_watch(args),
+    Some("cache-clean") => match args.next() {
+      Some(arg) => Err(CliError::UnexpectedArgument(arg)),
+      None => Ok(Command::CacheClean)
+    },
+    Some("compare") => parse_compare(args),
+    Some("gen") => parse_gen(args),
+    Some("isolated") => match args.next() {
+      Some(arg) => Err(CliError::UnexpectedArgument(arg)),
+      None => Ok(Command::Isolated)
+    },
+    Some("bench") => parse_bench(args),
+    Some("help") => match args.next() {
+      Some(command) if command_help(&command).is_some() => Ok(Command::Help(Some(command))),
+      Some(command) => Err(CliError::UnknownCommand(command)),
+      None => Ok(Command::Help(None))
+    },
+    Some("--help") | Some("-h") => Ok(Command::Help(None)),
+    Some(command) => Err(CliError::UnknownCommand(command.to_string()))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(String::from).collect()
+  }
+
+  #[test]
+  fn parse_args_reads_day_part_and_input() {
+    assert_eq!(
+      parse_args(args("run 7 --part 2 --input data/day7/input_example.txt --format json --jobs 2 --no-cache --explain --memory")),
+      Ok(Command::Run {
+        days: DaySelection::Day(7),
+        part: Some(2),
+        input: Some("data/day7/input_example.txt".to_string()),
+        options: RunOptions { format: Format::Json, jobs: Some(2), no_cache: true, explain: true, memory: true, ..RunOptions::default() },
+        overrides: Overrides::default()
+      })
+    );
+  }
+
+  #[test]
+  fn parse_args_defaults_to_running_all_days() {
+    assert_eq!(parse_args(args("")), parse_args(args("run all")));
+  }
+
+  #[test]
+  fn parse_args_rejects_invalid_part() {
+    assert_eq!(
+      parse_args(args("run 1 --part 3")),
+      Err(CliError::InvalidValue("--part", "3".to_string()))
+    );
+  }
+
+  #[test]
+  fn parse_args_reads_verify_answers_path() {
+    assert_eq!(
+      parse_args(args("verify --answers other.toml")),
+      Ok(Command::Verify { answers: Some("other.toml".to_string()) })
+    );
+  }
+
+  #[test]
+  fn parse_args_reads_bench_options() {
+    assert_eq!(
+      parse_args(args("bench 9 --part 2 --iterations 5 --baseline bench.toml --threshold 2.5")),
+      Ok(Command::Bench {
+        days: DaySelection::Day(9),
+        part: Some(2),
+        iterations: Some(5),
+        save: None,
+        baseline: Some("bench.toml".to_string()),
+        threshold: Some(2.5),
+        overrides: Overrides::default()
+      })
+    );
+    assert!(parse_args(args("bench 9 --timeout 500")).is_err());
+  }
+
+  #[test]
+  fn parse_args_reads_config_overrides() {
+    match parse_args(args("run 9 --config other.toml --data-dir puzzles --param players=9 --param last_marble=25 --strategy vec --timeout 500")) {
+      Ok(Command::Run { overrides, .. }) => assert_eq!(overrides, Overrides {
+        config: Some("other.toml".to_string()),
+        data_dir: Some("puzzles".to_string()),
+        params: vec![("players".to_string(), 9), ("last_marble".to_string(), 25)],
+        strategy: Some("vec".to_string()),
+        timeout_ms: Some(500)
+      }),
+      other => panic!("Unexpected command: {:?}", other)
+    }
+  }
+
+  #[test]
+  fn parse_args_rejects_malformed_param() {
+    assert_eq!(
+      parse_args(args("run 7 --param workers")),
+      Err(CliError::InvalidValue("--param", "workers".to_string()))
+    );
+    assert!(parse_args(args("run 7 --param workers=many")).is_err());
+  }
+
+  #[test]
+  fn parse_args_reads_fetch_day() {
+    assert_eq!(
+      parse_args(args("fetch 5 --data-dir puzzles")),
+      Ok(Command::Fetch {
+        days: DaySelection::Day(5),
+        overrides: Overrides { data_dir: Some("puzzles".to_string()), ..Overrides::default() }
+      })
+    );
+  }
+
+  #[test]
+  fn parse_args_reads_submitted_answer() {
+    assert_eq!(
+      parse_args(args("submit 2 2 tjxmoewpdkyaihvrndfluwbzc --ledger other.toml")),
+      Ok(Command::Submit {
+        day: 2,
+        part: 2,
+        answer: Some(Answer::Text("tjxmoewpdkyaihvrndfluwbzc".to_string())),
+        input: None,
+        ledger: Some("other.toml".to_string()),
+        overrides: Overrides::default()
+      })
+    );
+    assert!(matches!(parse_args(args("submit 1 1 -5")), Ok(Command::Submit { answer: Some(Answer::Number(-5)), .. })));
+  }
+
+  #[test]
+  fn parse_args_reads_compare_options() {
+    assert_eq!(
+      parse_args(args("compare 9 --part 2 --input big.txt --timeout 1000")),
+      Ok(Command::Compare {
+        days: DaySelection::Day(9),
+        part: Some(2),
+        input: Some("big.txt".to_string()),
+        overrides: Overrides { timeout_ms: Some(1000), ..Overrides::default() }
+      })
+    );
+    assert!(parse_args(args("compare all --input big.txt")).is_err());
+  }
+
+  #[test]
+  fn parse_args_reads_gen_options() {
+    assert_eq!(
+      parse_args(args("gen 8 --seed 42 --size 100 --output data/day8/generated.txt")),
+      Ok(Command::Gen { day: 8, seed: Some(42), size: Some(100), output: Some("data/day8/generated.txt".to_string()) })
+    );
+    assert!(parse_args(args("gen 8 --seed -1")).is_err());
+  }
+
+  #[test]
+  fn parse_args_reads_isolation_limits() {
+    match parse_args(args("run 8 --isolate --cpu-limit 5 --memory-limit 256")) {
+      Ok(Command::Run { options, .. }) => assert_eq!(
+        (options.isolate, options.cpu_limit, options.memory_limit), (true, Some(5), Some(256))
+      ),
+      other => panic!("Unexpected command: {:?}", other)
+    }
+    assert!(parse_args(args("run 8 --cpu-limit 5")).is_err());
+    assert!(parse_args(args("run 8 --isolate --explain")).is_err());
+  }
+
+  #[test]
+  fn parse_args_reads_help_command() {
+    assert_eq!(parse_args(args("help bench")), Ok(Command::Help(Some("bench".to_string()))));
+    assert_eq!(parse_args(args("help")), parse_args(args("--help")));
+    assert_eq!(parse_args(args("help jump")), Err(CliError::UnknownCommand("jump".to_string())));
+    assert!(command_help("run").unwrap().contains("--timeout"));
+  }
+
+  #[test]
+  fn parse_args_rejects_input_for_all_days() {
+    assert!(parse_args(args("run all --input some.txt")).is_err());
+  }
+}
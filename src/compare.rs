@@ -0,0 +1,121 @@
+use std::time::{Duration, Instant};
+
+use crate::error::Result;
+use crate::registry::Day;
+use crate::runner::{self, Status};
+use crate::solver::Context;
+
+/// Name shown for parts without alternative strategies
+pub const DEFAULT_STRATEGY: &str = "default";
+
+/// Answer and time of a single strategy of a part
+#[derive(Debug, Clone)]
+pub struct Timing {
+  pub day: u8,
+  pub part: u8,
+  pub strategy: String,
+  pub elapsed: Duration,
+  pub status: Status
+}
+
+/// Parses the input once and solves every selected part with each of its strategies, each
+/// one within the budget of the context
+pub fn compare_day(day: &Day, path: &str, parts: &[u8], ctx: &Context) -> Result<Vec<Timing>> {
+  let input = day.load(path)?;
+  let mut timings = Vec::new();
+
+  for part in parts {
+    let strategies: Vec<Option<&str>> = match day.solver.strategies(*part) {
+      names if names.is_empty() => vec![None],
+      names => names.into_iter().map(Some).collect()
+    };
+
+    for strategy in strategies {
+      let ctx = ctx.clone().with_strategy(strategy.map(String::from));
+      let start = Instant::now();
+      let (status, _) = runner::solve_within_budget(day, *part, &*input, &ctx);
+
+      timings.push(Timing {
+        day: day.day,
+        part: *part,
+        strategy: strategy.unwrap_or(DEFAULT_STRATEGY).to_string(),
+        elapsed: start.elapsed(),
+        status
+      });
+    }
+  }
+
+  Ok(timings)
+}
+
+/// Day and part of every part whose strategies failed or didn't give the same answer
+pub fn disagreements(timings: &[Timing]) -> Vec<(u8, u8)> {
+  let mut parts: Vec<(u8, u8)> = timings.iter().map(|t| (t.day, t.part)).collect();
+  parts.dedup();
+
+  parts.into_iter()
+    .filter(|(day, part)| {
+      let mut statuses = timings.iter().filter(|t| t.day == *day && t.part == *part).map(|t| &t.status);
+      let first = statuses.next();
+      first.is_none_or(|first| !matches!(first, Status::Solved(_)) || statuses.any(|s| s != first))
+    })
+    .collect()
+}
+
+/// Renders the timings as a plain text table, parts whose strategies disagree are flagged
+pub fn render_table(timings: &[Timing]) -> String {
+  let disagreeing = disagreements(timings);
+  let mut table = format!("{:>3}  {:>4}  {:<12}  {:>12}  {}\n", "Day", "Part", "Strategy", "Time (ms)", "Answer");
+
+  for t in timings {
+    let answer = match &t.status {
+      Status::Solved(answer) => answer.to_string(),
+      Status::Failed(e) => format!("ERROR {}", e),
+      Status::TimedOut(budget) => format!("TIMEOUT after {:?}", budget)
+    };
+    let flag = if disagreeing.contains(&(t.day, t.part)) { "  MISMATCH" } else { "" };
+
+    table += &format!(
+      "{:>3}  {:>4}  {:<12}  {:>12.3}  {}{}\n",
+      t.day, t.part, t.strategy, t.elapsed.as_secs_f64() * 1000.0, answer, flag
+    );
+  }
+
+  table
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::registry;
+  use crate::solver::Answer;
+
+  #[test]
+  fn strategies_agree_on_examples() {
+    let examples: Vec<(u8, &str, usize)> = vec![
+      #[cfg(feature = "day3")] (3, "data/day3/example.txt", 4),
+      #[cfg(feature = "day9")] (9, "data/day9/example1.txt", 3),
+    ];
+
+    for (day, path, strategies) in examples {
+      let day = registry::find(day).expect("Day is not registered");
+      let timings = compare_day(&day, path, &[1, 2], &Context::default()).expect("Unable to compare strategies");
+
+      assert_eq!(timings.len(), strategies);
+      assert!(disagreements(&timings).is_empty(), "\n{}", render_table(&timings));
+    }
+  }
+
+  #[test]
+  fn different_answers_are_flagged() {
+    let timing = |strategy: &str, answer: i64| Timing {
+      day: 1, part: 1, strategy: strategy.to_string(), elapsed: Duration::default(), status: Status::Solved(Answer::Number(answer))
+    };
+
+    assert_eq!(disagreements(&[timing("a", 1), timing("b", 2)]), vec![(1, 1)]);
+    assert!(disagreements(&[timing("a", 1), timing("b", 1)]).is_empty());
+
+    let timed_out = Timing { status: Status::TimedOut(Duration::from_millis(5)), ..timing("a", 1) };
+    assert_eq!(disagreements(&[timed_out.clone(), timed_out]), vec![(1, 1)]);
+  }
+}
@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+use serde::Deserialize;
+
+use crate::error::{Error, Result};
+use crate::solver::{Context, Params};
+use crate::utils::read_toml;
+
+pub const DEFAULT_CONFIG_PATH: &str = "aoc.toml";
+pub const DEFAULT_DATA_DIR: &str = "data";
+pub const DEFAULT_INPUT: &str = "input.txt";
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com/2018";
+
+/// Settings of a single `[dayN]` table. Every key other than `input`, `strategy` and
+/// `timeout_ms` is a named puzzle parameter
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct DayConfig {
+  pub input: Option<String>,
+  pub strategy: Option<String>,
+  /// Time budget of each part of the day, overrides the top level one
+  pub timeout_ms: Option<u64>,
+  #[serde(flatten)]
+  pub params: Params
+}
+
+#[derive(Debug, Deserialize)]
+struct ConfigFile {
+  data_dir: Option<String>,
+  base_url: Option<String>,
+  session: Option<String>,
+  timeout_ms: Option<u64>,
+  #[serde(flatten)]
+  days: BTreeMap<String, DayConfig>
+}
+
+/// Project configuration, read from `aoc.toml`:
+///
+/// ```toml
+/// data_dir = "data"
+/// base_url = "https://adventofcode.com/2018"
+/// timeout_ms = 10000
+///
+/// [day7]
+/// input = "input.txt"
+/// workers = 5
+///
+/// [day9]
+/// strategy = "linked-list"
+/// timeout_ms = 60000
+/// ```
+///
+/// Relative input paths are resolved against `<data_dir>/dayN`. The session token
+/// used to fetch inputs can be kept here too, though the environment is a safer place
+#[derive(Debug, Clone)]
+pub struct Config {
+  pub data_dir: String,
+  pub base_url: String,
+  pub session: Option<String>,
+  /// Time budget of each part, unlimited when not set
+  pub timeout_ms: Option<u64>,
+  days: BTreeMap<u8, DayConfig>
+}
+
+impl Default for Config {
+  fn default() -> Config {
+    Config {
+      data_dir: DEFAULT_DATA_DIR.to_string(),
+      base_url: DEFAULT_BASE_URL.to_string(),
+      session: None,
+      timeout_ms: None,
+      days: BTreeMap::new()
+    }
+  }
+}
+
+impl Config {
+  pub fn load(path: &str) -> Result<Config> {
+    let file: ConfigFile = read_toml(path)?;
+    let mut days = BTreeMap::new();
+
+    for (key, day_config) in file.days {
+      let day = key.strip_prefix("day")
+        .and_then(|d| d.parse::<u8>().ok())
+        .ok_or_else(|| Error::InvalidInput(format!("Unexpected key {} in {}, expected data_dir, base_url, session, timeout_ms or [dayN]", key, path)))?;
+      days.insert(day, day_config);
+    }
+
+    Ok(Config {
+      data_dir: file.data_dir.unwrap_or_else(|| DEFAULT_DATA_DIR.to_string()),
+      base_url: file.base_url.unwrap_or_else(|| DEFAULT_BASE_URL.to_string()),
+      session: file.session,
+      timeout_ms: file.timeout_ms,
+      days
+    })
+  }
+
+  /// Loads the configuration, or falls back to the defaults when the file doesn't exist
+  pub fn load_or_default(path: &str) -> Result<Config> {
+    if Path::new(path).exists() {
+      Config::load(path)
+    } else {
+      Ok(Config::default())
+    }
+  }
+
+  pub fn day(&self, day: u8) -> DayConfig {
+    self.days.get(&day).cloned().unwrap_or_default()
+  }
+
+  pub fn day_mut(&mut self, day: u8) -> &mut DayConfig {
+    self.days.entry(day).or_default()
+  }
+
+  pub fn day_dir(&self, day: u8) -> String {
+    Path::new(&self.data_dir).join(format!("day{}", day)).to_string_lossy().to_string()
+  }
+
+  pub fn input(&self, day: u8) -> String {
+    let input = self.day(day).input.unwrap_or_else(|| DEFAULT_INPUT.to_string());
+    Path::new(&self.day_dir(day)).join(input).to_string_lossy().to_string()
+  }
+
+  pub fn params(&self, day: u8) -> Params {
+    self.day(day).params
+  }
+
+  /// Context the day is solved in, with its parameters, strategy and time budget
+  pub fn context(&self, day: u8) -> Context {
+    let day_config = self.day(day);
+    let budget = day_config.timeout_ms.or(self.timeout_ms).map(Duration::from_millis);
+
+    Context::with_params(day_config.params).with_strategy(day_config.strategy).with_budget(budget)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn config_resolves_inputs_and_params() {
+    let path = std::env::temp_dir().join("aoc-config-test.toml");
+    std::fs::write(&path, "data_dir = \"puzzles\"\ntimeout_ms = 500\n\n[day7]\ninput = \"big.txt\"\nstrategy = \"fast\"\nworkers = 2\ntimeout_ms = 20\n").unwrap();
+    let config = Config::load(&path.to_string_lossy()).expect("Unable to load config");
+
+    assert_eq!(config.input(7), Path::new("puzzles/day7/big.txt").to_string_lossy());
+    assert_eq!(config.input(1), Path::new("puzzles/day1/input.txt").to_string_lossy());
+    assert_eq!(config.params(7).get("workers"), Some(2));
+    assert_eq!(config.context(7).strategy.as_deref(), Some("fast"));
+    assert!(config.params(1).is_empty());
+    assert_eq!(config.context(7).budget, Some(Duration::from_millis(20)));
+    assert_eq!(config.context(1).budget, Some(Duration::from_millis(500)));
+  }
+}
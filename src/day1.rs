@@ -1,48 +1,153 @@
-use std::collections::HashSet;
-use std::iter::{repeat};
+use std::collections::{HashMap, HashSet};
 
-use crate::utils::{read_input};
+use crate::error::{Error, Result};
+use crate::solver::{Answer, Context, Solver};
+use crate::utils::RawInput;
 
-fn read_input_file(path: &str) -> Vec<i32> {
-  read_input(path).iter().enumerate().map(|(i, line): (usize, &String)|
-    line.parse::<i32>().unwrap_or_else(|_| panic!(
-      "Unable to parse line {}, '{}' to a number",
-      i, line
-    ))
+pub struct Day1;
+
+fn parse_frequencies<'a, I: Iterator<Item = &'a str>>(lines: I) -> Result<Vec<i32>> {
+  lines.enumerate().map(|(i, line): (usize, &str)|
+    line.parse::<i32>().map_err(|_|
+      Error::parse(1, format!("Unable to parse '{}' to a number", line)).at_line(i + 1)
+    )
   ).collect()
 }
 
-/// Seems to be a simple case of reading the input and summing it
-pub fn solve_part1(path: &str) -> i32 {
-  read_input_file(path).iter().sum()
+/// Seems to be a simple case of reading the input and summing it. Summed as i64, so a long
+/// list of large changes can't overflow
+fn solve_part1(numbers: &[i32]) -> i64 {
+  numbers.iter().map(|n| i64::from(*n)).sum()
 }
 
 /// This one is trickier, we need to do the summing but also keep track of current sum and all
-/// the sums we've already encountered. We do it by having a state that keeps track of
-/// current sum - acc, and a HashSet reference that tracks all the sums that were encountered
-/// up to the point. Final trick is to use a short-circuting of Rust Scan to exit as soon as
-/// we encounter acc value that is already present in a set.
-pub fn solve_part2(path: &str) -> i32 {
-  let numbers = read_input_file(path);
-  let mut acc: i32 = 0;
-  let mut set: HashSet<i32> = HashSet::new();
-
-  repeat(numbers).flatten().scan((&mut acc, &mut set), |state, item| {
-    *state.0 += item; // Add current item to generate new sum
-    if state.1.contains(state.0) {
-      // Woohoo! We've already encountered this sum - stop the iterator, the acc will now be set
-      // to a sum that has already been seen once
-      None
-    } else {
-      // Add a new sum to the set and continue iteration
-      state.1.insert(*state.0);
-      Some(())
+/// the sums we've already encountered. The first pass is simulated, tracking all the sums that
+/// were encountered up to the point in a HashSet, and exits as soon as a sum is already there.
+///
+/// Simulating further passes could run for ever, or for billions of passes before anything
+/// repeats. Every sum of pass `d` is a sum of the first pass shifted by `d` times the total,
+/// so sum `i` repeats after `d` passes when `sums[i] + d * total` is one of the first pass
+/// sums. Those are in the same class modulo the total, so sorting each class gives the least
+/// `d` of every sum, and the first repeat is the one with the least `d`, then the least `i`.
+/// No two sums in the same class means no frequency is ever reached twice.
+///
+/// The repeat is explained with its iteration, the number of changes applied until the
+/// frequency was reached again. Both loops stop once the context is cancelled
+fn solve_part2(numbers: &[i32], ctx: &Context) -> Result<i64> {
+  let trace = &ctx.trace;
+  let mut acc: i64 = 0;
+  let mut seen: HashSet<i64> = HashSet::new();
+  let mut sums: Vec<i64> = Vec::with_capacity(numbers.len());
+
+  // Starting frequency counts as already seen, '+1, -1' repeats 0
+  for (i, number) in numbers.iter().enumerate() {
+    ctx.cancel.check()?;
+    seen.insert(acc);
+    sums.push(acc);
+    acc += i64::from(*number);
+
+    if seen.contains(&acc) {
+      trace.emit(0, "repeat", || vec![("frequency", acc.into()), ("iteration", (i + 1).into()), ("pass", 0.into())]);
+      return Ok(acc);
+    }
+  }
+
+  let total = acc;
+  trace.emit(0, "first-pass", || vec![("changes", numbers.len().into()), ("total", total.into())]);
+  if total == 0 {
+    // Only possible without any changes, otherwise the starting frequency is reached again
+    return Err(Error::NoSolution("There are no frequency changes".to_string()));
+  }
+
+  let mut classes: HashMap<i64, Vec<(i64, usize)>> = HashMap::new();
+  for (i, sum) in sums.iter().enumerate() {
+    classes.entry(sum.rem_euclid(total)).or_default().push((*sum, i));
+  }
+
+  // (passes, index of the sum, repeated frequency) of the earliest repeat so far
+  let mut first: Option<(i64, usize, i64)> = None;
+  for class in classes.values_mut() {
+    ctx.cancel.check()?;
+    class.sort_unstable();
+    if total < 0 {
+      class.reverse();
     }
-  })
-  // That's a one way to force full evaluation of an iterator, probably not the most elegant one
-  .count();
-  
-  // Once we reached this point, acc is set to a value we've seen twice. Alternatively, previous call
-  // to count will just run an endless loop if no sum is ever encountered twice
-  acc 
+
+    for pair in class.windows(2) {
+      let ((from, i), (to, _)) = (pair[0], pair[1]);
+      let passes = (to - from) / total;
+      if first.is_none_or(|(p, j, _)| (passes, i) < (p, j)) {
+        trace.emit(1, "candidate", || vec![("frequency", to.into()), ("pass", passes.into()), ("index", i.into())]);
+        first = Some((passes, i, to));
+      }
+    }
+  }
+
+  if let Some((passes, i, frequency)) = first {
+    let iteration = (passes as u64).saturating_mul(numbers.len() as u64).saturating_add(i as u64);
+    trace.emit(0, "repeat", || vec![("frequency", frequency.into()), ("iteration", iteration.into()), ("pass", passes.into())]);
+  }
+
+  first.map(|(_, _, frequency)| frequency)
+    .ok_or_else(|| Error::NoSolution("No frequency is ever reached twice".to_string()))
+}
+
+impl Solver for Day1 {
+  type Input = Vec<i32>;
+
+  // Sums are i64 and the first repeat is worked out without simulating every pass since version 2
+  const VERSION: &'static str = "2";
+
+  fn parse(&self, input: &RawInput) -> Result<Vec<i32>> {
+    parse_frequencies(input.lines())
+  }
+
+  fn part1(&self, input: &Vec<i32>, _ctx: &Context) -> Result<Answer> {
+    Ok(solve_part1(input).into())
+  }
+
+  fn part2(&self, input: &Vec<i32>, ctx: &Context) -> Result<Answer> {
+    Ok(solve_part2(input, ctx)?.into())
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::cancel::Cancellation;
+  use crate::trace::Trace;
+
+  #[test]
+  fn repeat_is_found_in_later_passes() {
+    assert_eq!(solve_part2(&[1, -1], &Context::default()).unwrap(), 0);
+    assert_eq!(solve_part2(&[3, 3, 4, -2, -4], &Context::default()).unwrap(), 10);
+    assert_eq!(solve_part2(&[-6, 3, 8, 5, -6], &Context::default()).unwrap(), 5);
+    assert_eq!(solve_part2(&[7, 7, -2, -7, -4], &Context::default()).unwrap(), 14);
+    assert_eq!(solve_part2(&[-1_000_000_000, 999_999_999], &Context::default()).unwrap(), -1_000_000_000);
+  }
+
+  #[test]
+  fn frequencies_that_never_repeat_are_reported() {
+    assert!(matches!(solve_part2(&[1, 1], &Context::default()), Err(Error::NoSolution(_))));
+    assert!(matches!(solve_part2(&[], &Context::default()), Err(Error::NoSolution(_))));
+    assert_eq!(solve_part1(&[i32::MAX, i32::MAX]), 2 * i64::from(i32::MAX));
+  }
+
+  #[test]
+  fn cancelled_search_stops() {
+    let cancel = Cancellation::new();
+    cancel.cancel();
+
+    assert!(matches!(solve_part2(&[1, 1], &Context::default().with_cancel(cancel)), Err(Error::Cancelled)));
+  }
+
+  #[test]
+  fn repeat_is_explained_with_its_iteration() {
+    let trace = Trace::enabled();
+    assert_eq!(solve_part2(&[3, 3, 4, -2, -4], &Context::default().with_trace(trace.clone())).unwrap(), 10);
+
+    let repeat = trace.events().pop().unwrap();
+    assert_eq!(repeat.name, "repeat");
+    assert_eq!(repeat.fields, vec![("frequency", 10.into()), ("iteration", 7.into()), ("pass", 1.into())]);
+  }
 }
@@ -1,6 +1,10 @@
 use std::collections::HashMap;
 
-use crate::utils::{read_input};
+use crate::error::{Error, Result};
+use crate::solver::{Answer, Context, Solver};
+use crate::utils::RawInput;
+
+pub struct Day2;
 
 /// Simply, for every box_id count the number of letters, then return
 /// a tuple that indicates if it belongs to 2-letter and 3-letter
@@ -25,9 +29,9 @@ fn categorize(box_id: &str) -> (i32, i32) {
 /// Fairly straightforward, categorize every id, sum the results and
 /// multiply values in the result tuple - that should be the
 /// checksum
-pub fn solve_part1(path: &str) -> i32 {
-  let checksum_components: (i32, i32) = read_input(path).iter()
-    .map(|line| categorize(&line))
+fn solve_part1(input: &[String]) -> i32 {
+  let checksum_components: (i32, i32) = input.iter()
+    .map(|line| categorize(line))
     .fold((0, 0), |acc, i| (acc.0 + i.0, acc.1 + i.1));
 
   checksum_components.0 * checksum_components.1
@@ -65,20 +69,34 @@ fn are_close(left_id: &str, right_id: &str) -> bool {
 /// as the first match is found. Assuming there is a match, this
 /// should never to more than (n over 2) iterations, since
 /// 'are_close' comparision is comutative
-pub fn solve_part2(path: &str) -> String {
-  let input = read_input(path);
+fn solve_part2(input: &[String]) -> Result<String> {
   let matched = input.iter().flat_map(|left_id|
     input.iter().map(move |right_id| (left_id, right_id))
   )
   .find(|pair| are_close(pair.0, pair.1))
-  .unwrap_or_else(|| panic!("Couldn't find close pair"));
+  .ok_or_else(|| Error::NoSolution("Couldn't find close pair".to_string()))?;
 
-  matched.0.chars().zip(matched.1.chars())
+  Ok(matched.0.chars().zip(matched.1.chars())
     .filter(|(l, r)| l == r)
     .map(|t| t.0)
-    .collect()
+    .collect())
 }
 
+impl Solver for Day2 {
+  type Input = Vec<String>;
+
+  fn parse(&self, input: &RawInput) -> Result<Vec<String>> {
+    Ok(input.lines().map(String::from).collect())
+  }
+
+  fn part1(&self, input: &Vec<String>, _ctx: &Context) -> Result<Answer> {
+    Ok(solve_part1(input).into())
+  }
+
+  fn part2(&self, input: &Vec<String>, _ctx: &Context) -> Result<Answer> {
+    Ok(solve_part2(input)?.into())
+  }
+}
 
 #[cfg(test)]
 mod test {
@@ -1,48 +1,67 @@
 use std::cmp::{max, min};
 use std::collections::{HashSet};
-use std::result::Result;
+use std::convert::TryFrom;
 
 use regex::Regex;
-use crate::utils::{read_input};
+use crate::error::{Error, Result};
+use crate::solver::{Answer, Context, Solver, Strategy};
+use crate::utils::RawInput;
 
 lazy_static! {
   static ref CLAIM_MATCHER: Regex = Regex::new(".?([0-9]+) @ ([0-9]+),([0-9]+): ([0-9]+)x([0-9]+).*")
     .unwrap();
 }
 
-#[derive(Debug)]
-enum ClaimError {
-  ParseLineError(String),
-  ParseFieldError(String, String)
+const FABRIC_SIZE: usize = 1000;
+
+/// Rectangle of fabric between its top left corner and the bottom right one, which is
+/// just outside of it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Area {
+  pub left: i32,
+  pub top: i32,
+  pub right: i32,
+  pub bottom: i32
+}
+
+impl Area {
+  pub fn size(&self) -> i64 {
+    max(0, i64::from(self.right) - i64::from(self.left)) * max(0, i64::from(self.bottom) - i64::from(self.top))
+  }
+
+  pub fn points(&self) -> impl Iterator<Item = (i32, i32)> {
+    let Area { left, top, right, bottom } = *self;
+    (left .. right).flat_map(move |x| (top .. bottom).map(move |y| (x, y)))
+  }
 }
 
 #[derive(Debug)]
-struct Claim {
-  id: i32,
-  x: i32,
-  y: i32,
-  width: i32,
-  height: i32
+pub struct Claim {
+  pub id: i32,
+  pub x: i32,
+  pub y: i32,
+  pub width: i32,
+  pub height: i32
 }
 
 impl Claim {
-  fn from_input(line: &str) -> Result<Claim, ClaimError> {
-    let fields = vec!("id", "x", "y", "width", "height");
+  pub fn from_input(line: &str) -> Result<Claim> {
+    let fields = ["id", "x", "y", "width", "height"];
     let captures = CLAIM_MATCHER.captures(line)
-      .ok_or_else(|| ClaimError::ParseLineError(line.to_string()))?;
+      .ok_or_else(|| Error::parse(1, format!("Unable to parse claim: {}", line)))?;
 
-    let matched_field_values: Result<Vec<i32>, ClaimError> = fields.iter()
+    let matched_field_values: Result<Vec<i32>> = fields.iter()
       .zip(captures.iter()
         .skip(1)
-        .map(|o| o.unwrap().as_str())
+        .flatten()
       )
-      .map(|(field, str_value)| {
-        str_value.parse::<i32>().or_else(|_| 
-          Err(ClaimError::ParseFieldError(
-            field.to_string(),
-            str_value.to_string()
+      .map(|(field, value)| {
+        value.as_str().parse::<i32>().map_err(|_|
+          Error::parse(
+            value.start() + 1,
+            format!("Unable to parse claim {} from {:?}", field, value.as_str())
           )
-        ))
+        )
       })
       .collect();
 
@@ -57,21 +76,31 @@ impl Claim {
     })
   }
 
-  fn points(&self) -> HashSet<(usize, usize)> {
-    let mut set: HashSet<(usize, usize)> = HashSet::new();
-    for i in self.x .. self.x + self.width {
-      for j in self.y .. self.y + self.height {
-        set.insert((i as usize, j as usize));
-      }
-    }
-    set
+  /// Area of the fabric the claim covers, `None` when its corners don't fit into the coordinates
+  pub fn area(&self) -> Option<Area> {
+    Some(Area {
+      left: self.x,
+      top: self.y,
+      right: self.x.checked_add(self.width)?,
+      bottom: self.y.checked_add(self.height)?
+    })
   }
 
-  fn overlaps_with(&self, claim: &Claim) -> bool {
-    let ox = max(0, min(self.x + self.width, claim.x + claim.width) - max(self.x, claim.x));
-    let oy = max(0, min(self.y + self.height, claim.y + claim.height) - max(self.y, claim.y));
+  /// Area both of the claims cover, worked out from their corners
+  pub fn overlap(&self, claim: &Claim) -> Option<Area> {
+    let (a, b) = (self.area()?, claim.area()?);
+    let overlap = Area {
+      left: max(a.left, b.left),
+      top: max(a.top, b.top),
+      right: min(a.right, b.right),
+      bottom: min(a.bottom, b.bottom)
+    };
+
+    if overlap.left < overlap.right && overlap.top < overlap.bottom { Some(overlap) } else { None }
+  }
 
-    ox > 0 && oy > 0
+  pub fn overlaps_with(&self, claim: &Claim) -> bool {
+    self.overlap(claim).is_some()
   }
 }
 
@@ -84,7 +113,7 @@ impl Fabric {
   fn new(width: usize, height: usize) -> Fabric {
     Fabric {
       points: vec![0; width * height],
-      width: width
+      width
     }
   }
 
@@ -96,13 +125,33 @@ impl Fabric {
     let i = self.point_index(x, y);
     self.points[i] += 1;
   }
+
+  fn claims_at(&self, x: usize, y: usize) -> usize {
+    self.points[self.point_index(x, y)]
+  }
+}
+
+fn claimed_fabric(claims: &[Claim]) -> Fabric {
+  let mut fabric = Fabric::new(FABRIC_SIZE, FABRIC_SIZE);
+  claims.iter()
+    .flat_map(|claim| claim.area().into_iter().flat_map(|area| area.points()))
+    .for_each(|(x, y)| fabric.inc_point(x as usize, y as usize));
+  fabric
 }
 
-fn load_claims(path: &str) -> Vec<Claim> {
-  let lines = read_input(path);
-  let parsed_claims: Result<Vec<Claim>, _> = lines.iter()
-    .map(|s| Claim::from_input(s)).collect();
-  parsed_claims.expect("An error occured while parsing claims")  
+pub struct Day3;
+
+fn load_claims<'a, I: Iterator<Item = &'a str>>(lines: I) -> Result<Vec<Claim>> {
+  lines.enumerate()
+    .map(|(i, s)| {
+      let claim = Claim::from_input(s).map_err(|e| e.at_line(i + 1))?;
+      let fits = |start: i32, length: i32| i64::from(start) + i64::from(length) <= FABRIC_SIZE as i64;
+      if !fits(claim.x, claim.width) || !fits(claim.y, claim.height) {
+        return Err(Error::InvalidInput(format!("Claim #{} doesn't fit on the fabric", claim.id)));
+      }
+      Ok(claim)
+    })
+    .collect()
 }
 
 /// Not a big fan of how I solved this but I ran out of ideas. Basically, just tally up how many
@@ -111,16 +160,22 @@ fn load_claims(path: &str) -> Vec<Claim> {
 /// for CPU cache.
 /// 
 /// Still, I have a feeling there must be a better way
-pub fn solve_part1(path: &str) -> usize {
-  let claims = load_claims(path);
-  let mut fabric = Fabric::new(1000, 1000);
+fn solve_part1(claims: &[Claim]) -> usize {
+  claimed_fabric(claims).points.iter().filter(|v| **v > 1).count()
+}
 
-  let fabric2 = claims.iter().fold(&mut fabric, |f, claim| {
-    claim.points().iter().for_each(|p| f.inc_point(p.0, p.1));
-    f
-  });
+/// Same as the first part, without the grid: collects the points of the overlap
+/// of every pair of overlapping claims
+fn solve_part1_overlap(claims: &[Claim]) -> usize {
+  let mut overlapping: HashSet<(i32, i32)> = HashSet::new();
+
+  for (i, c1) in claims.iter().enumerate() {
+    for overlap in claims[i + 1 ..].iter().filter_map(|c2| c1.overlap(c2)) {
+      overlapping.extend(overlap.points());
+    }
+  }
 
-  fabric2.points.iter().filter(|v| **v > 1).count()
+  overlapping.len()
 }
 
 /// Another O(n^2) solution where I go through entire search space.
@@ -130,13 +185,51 @@ pub fn solve_part1(path: &str) -> usize {
 /// 
 /// Also took me way to long to figure out that c1.overlaps_with(c2) is always
 /// true when c1 == c2 😳
-pub fn solve_part2(path: &str) -> i32 {
-  let claims = load_claims(path);
-
+fn solve_part2(claims: &[Claim]) -> Result<i32> {
   claims.iter()
     .find(|c1| !claims.iter().any(|c2| c1.id != c2.id && c1.overlaps_with(c2)))
-    .expect("Can't find non-overlaping claim")
-    .id
+    .map(|c| c.id)
+    .ok_or_else(|| Error::NoSolution("Can't find non-overlaping claim".to_string()))
+}
+
+/// Same as the second part, using the grid of the first part: the claim we're looking
+/// for is the only one on all of its points
+fn solve_part2_grid(claims: &[Claim]) -> Result<i32> {
+  let fabric = claimed_fabric(claims);
+
+  claims.iter()
+    .find(|c| c.area().is_some_and(|area| area.points().all(|(x, y)| fabric.claims_at(x as usize, y as usize) == 1)))
+    .map(|c| c.id)
+    .ok_or_else(|| Error::NoSolution("Can't find non-overlaping claim".to_string()))
+}
+
+impl Solver for Day3 {
+  type Input = Vec<Claim>;
+
+  fn parse(&self, input: &RawInput) -> Result<Vec<Claim>> {
+    load_claims(input.lines())
+  }
+
+  fn part1(&self, input: &Vec<Claim>, _ctx: &Context) -> Result<Answer> {
+    Answer::try_from(solve_part1(input))
+  }
+
+  fn part2(&self, input: &Vec<Claim>, _ctx: &Context) -> Result<Answer> {
+    Ok(solve_part2(input)?.into())
+  }
+
+  fn strategies(&self, part: u8) -> Vec<Strategy<Vec<Claim>>> {
+    match part {
+      1 => vec![
+        Strategy { name: "grid", solve: |claims, _| Answer::try_from(solve_part1(claims)) },
+        Strategy { name: "overlap", solve: |claims, _| Answer::try_from(solve_part1_overlap(claims)) }
+      ],
+      _ => vec![
+        Strategy { name: "overlap", solve: |claims, _| Ok(solve_part2(claims)?.into()) },
+        Strategy { name: "grid", solve: |claims, _| Ok(solve_part2_grid(claims)?.into()) }
+      ]
+    }
+  }
 }
 
 #[cfg(test)]
@@ -145,7 +238,7 @@ mod test {
 
   #[test]
   fn claim_should_parse_correct_input_line() {
-    let claim = Claim::from_input(&"#123 @ 12,15: 10x25".to_string()).expect("Unable to parse claim");
+    let claim = Claim::from_input("#123 @ 12,15: 10x25").expect("Unable to parse claim");
 
     assert_eq!(claim.id, 123);
     assert_eq!(claim.x, 12);
@@ -153,4 +246,26 @@ mod test {
     assert_eq!(claim.width, 10);
     assert_eq!(claim.height, 25);
   }
+
+  #[test]
+  fn overlap_is_worked_out_from_corners() {
+    let claim = |line: &str| Claim::from_input(line).expect("Unable to parse claim");
+    let huge = Claim { id: 3, x: i32::MAX, y: 0, width: i32::MAX, height: 1 };
+
+    assert_eq!(
+      claim("#1 @ 1,3: 4x4").overlap(&claim("#2 @ 3,1: 4x4")),
+      Some(Area { left: 3, top: 3, right: 5, bottom: 5 })
+    );
+    assert_eq!(claim("#1 @ 1,3: 4x4").overlap(&claim("#2 @ 5,3: 4x4")), None);
+    assert_eq!(huge.area(), None);
+    assert!(!huge.overlaps_with(&claim("#1 @ 1,0: 4x4")));
+  }
+
+  #[test]
+  fn claim_should_report_column_of_invalid_field() {
+    match Claim::from_input("#123 @ 12,15: 99999999999x25") {
+      Err(Error::Parse { column, .. }) => assert_eq!(column, 15),
+      r => panic!("Expected parse error, got {:?}", r)
+    }
+  }
 }
\ No newline at end of file
@@ -2,11 +2,14 @@ use std::cmp::{max, min};
 use std::collections::BTreeMap;
 use chrono::prelude::*;
 use chrono::Duration;
-use regex::Regex;
+use regex::{Captures, Regex};
 
-use crate::utils::read_input;
+use crate::error::{Error, Result};
+use crate::solver::{Answer, Context, Solver};
+use crate::trace::Trace;
+use crate::utils::RawInput;
 
-static DATE_TIME_FORMAT: &'static str = "%Y-%m-%d %H:%M";
+static DATE_TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
 
 lazy_static! {
   static ref SHIFT_START_MATCHER: Regex = Regex::new("^\\[(.+)\\] Guard #([0-9]+) begins shift$")
@@ -22,7 +25,7 @@ enum GuardState {
   Asleep
 }
 
-struct Guard {
+pub struct Guard {
   current_state: GuardState,
   current_state_change: NaiveDateTime,
   minutes_asleep: Vec<i32>
@@ -37,130 +40,172 @@ impl Guard {
     }
   }
 
-  pub fn starts_shift(&mut self, at: NaiveDateTime) {
-    if let GuardState::Asleep = self.current_state { self.wakes_up(at) }
+  /// Number of times the guard was asleep during each minute of the midnight hour
+  pub fn minutes_asleep(&self) -> &[i32] {
+    &self.minutes_asleep
+  }
+
+  pub fn starts_shift(&mut self, at: NaiveDateTime) -> Result<()> {
+    if let GuardState::Asleep = self.current_state { self.wakes_up(at)?; }
+    Ok(())
   }
 
   fn report_asleep(&mut self, start_minute: usize, end_minute: usize) {
     let start = max(start_minute, 0);
     let end = min(end_minute, 59);
 
-    for m in &mut self.minutes_asleep[start .. end] {
-      *m += 1
+    if start < end {
+      for m in &mut self.minutes_asleep[start .. end] {
+        *m += 1
+      }
     }
   }
 
-  pub fn falls_asleep(&mut self, at: NaiveDateTime) {
+  pub fn falls_asleep(&mut self, at: NaiveDateTime) -> Result<()> {
     match self.current_state {
       GuardState::Awake => {
         self.current_state = GuardState::Asleep;
         self.current_state_change = at;
+        Ok(())
       }
-      _ => panic!("Guard cannot be more asleep")
+      _ => Err(Error::InvalidInput(format!("Guard cannot be more asleep at {}", at)))
     }
   }
 
-  pub fn wakes_up(&mut self, at: NaiveDateTime) {
+  pub fn wakes_up(&mut self, at: NaiveDateTime) -> Result<()> {
     match self.current_state {
       GuardState::Asleep => {
         let sleep_dur: Duration = at.signed_duration_since(self.current_state_change);
 
         self.report_asleep(
           self.current_state_change.minute() as usize,
-          (i64::from(self.current_state_change.minute()) + sleep_dur.num_minutes()) as usize
+          max(0, i64::from(self.current_state_change.minute()) + sleep_dur.num_minutes()) as usize
         );
 
         self.current_state = GuardState::Awake;
         self.current_state_change = at;
+        Ok(())
       }
-      _ => panic!("Guard cannot be more awake")
+      _ => Err(Error::InvalidInput(format!("Guard cannot be more awake at {}", at)))
+    }
+  }
+}
+
+/// Events of the log in chronological order, the guards are worked out from them when a
+/// part is solved
+pub struct ShiftLog {
+  events: Vec<ParsedLine>
+}
+
+impl ShiftLog {
+  /// Replays the events, explaining the transitions in chronological order with the naps
+  /// nested under their shift
+  pub fn guards_log(&self, trace: &Trace) -> Result<GuardsLog> {
+    let mut log = GuardsLog::new();
+    for event in &self.events {
+      log.handle_event(event, trace)?;
     }
+
+    Ok(log)
   }
 }
 
-struct GuardsLog {
+pub struct GuardsLog {
   current_guard_id: Option<i32>,
   guards: BTreeMap<i32, Guard>
 }
 
 impl GuardsLog {
-  pub fn new() -> GuardsLog {
+  fn new() -> GuardsLog {
     GuardsLog {
       current_guard_id: None,
       guards: BTreeMap::new()
     }
   }
-  fn current_guard(&mut self) -> &mut Guard {
-    let current_guard_id = self.current_guard_id.unwrap_or_else(|| 
-      panic!("Expected guard id to be set"));
+
+  /// Guards that appear in the log, keyed by their id
+  pub fn guards(&self) -> &BTreeMap<i32, Guard> {
+    &self.guards
+  }
+
+  fn current_guard(&mut self) -> Result<&mut Guard> {
+    let current_guard_id = self.current_guard_id.ok_or_else(||
+      Error::InvalidInput("Expected guard id to be set before the first shift starts".to_string()))?;
 
     self.guards.get_mut(&current_guard_id)
-      .unwrap_or_else(|| panic!("Expected guard {} to be available", current_guard_id))
+      .ok_or_else(|| Error::InvalidInput(format!("Expected guard {} to be available", current_guard_id)))
   }
 
-  fn handle_event(&mut self, line: &ParsedLine) {
+  fn handle_event(&mut self, line: &ParsedLine, trace: &Trace) -> Result<()> {
+    let (at, depth, state) = match line {
+      ParsedLine::ShiftStart(when, _) => (*when, 0, "shift"),
+      ParsedLine::FallAsleep(when) => (*when, 1, "asleep"),
+      ParsedLine::WakeUp(when) => (*when, 1, "awake")
+    };
+
     match line {
       ParsedLine::ShiftStart(when, guard_id) => {
         self.current_guard_id = Some(*guard_id);
         let guard: &mut Guard = self.guards.entry(*guard_id).or_insert_with(|| Guard::starts_first_shift(*when));
-        guard.starts_shift(*when);
+        guard.starts_shift(*when)
       },
       ParsedLine::FallAsleep(when) => { 
-        self.current_guard().falls_asleep(*when);
+        self.current_guard()?.falls_asleep(*when)
       },
       ParsedLine::WakeUp(when) => {
-        self.current_guard().wakes_up(*when);
+        self.current_guard()?.wakes_up(*when)
       }
+    }?;
+
+    if let Some(guard_id) = self.current_guard_id {
+      trace.emit(depth, state, || vec![
+        ("guard", guard_id.into()), ("at", at.format(DATE_TIME_FORMAT).to_string().into())
+      ]);
     }
+    Ok(())
   }
 }
 
 #[derive(Debug)]
-enum ParsedLine {
+pub(crate) enum ParsedLine {
   ShiftStart(NaiveDateTime, i32),
   FallAsleep(NaiveDateTime),
   WakeUp(NaiveDateTime)
 }
 
-fn parse_line(line: &str) -> ParsedLine {
-  if SHIFT_START_MATCHER.is_match(line) {
-    let captures = SHIFT_START_MATCHER.captures(line)
-      .unwrap_or_else(|| panic!("Invalid shift start line line: {}", line));
-    let date = NaiveDateTime::parse_from_str(&captures[1], DATE_TIME_FORMAT)
-      .unwrap_or_else(|e| panic!("Unable to parse date {:?}: {:?}", &captures[1], e));
-    let guard_id = &captures[2].parse::<i32>()
-      .unwrap_or_else(|_| panic!("Unable to parse guard id {:?}", &captures[2]));
-    
-    ParsedLine::ShiftStart(date, *guard_id)
-  } else if ASLEEP_MATCHER.is_match(line) {
-    let captures = ASLEEP_MATCHER.captures(line)
-      .unwrap_or_else(|| panic!("Invalid fall asleep line: {}", line));
-    let date = NaiveDateTime::parse_from_str(&captures[1], DATE_TIME_FORMAT)
-      .unwrap_or_else(|_| panic!("Unable to parse date {:?}", &captures[1]));
-    
-    ParsedLine::FallAsleep(date)
-  } else if WAKE_UP_MATCHER.is_match(line) {
-    let captures = WAKE_UP_MATCHER.captures(line)
-      .unwrap_or_else(|| panic!("Invalid wake up line: {}", line));
-    let date = NaiveDateTime::parse_from_str(&captures[1], DATE_TIME_FORMAT)
-      .unwrap_or_else(|_| panic!("Unable to parse date {:?}", &captures[1]));
+fn parse_date(captures: &Captures) -> Result<NaiveDateTime> {
+  NaiveDateTime::parse_from_str(&captures[1], DATE_TIME_FORMAT)
+    .map_err(|e| Error::parse(captures.get(1).map_or(1, |m| m.start() + 1), format!("Unable to parse date {:?}: {}", &captures[1], e)))
+}
+
+pub(crate) fn parse_line(line: &str) -> Result<ParsedLine> {
+  if let Some(captures) = SHIFT_START_MATCHER.captures(line) {
+    let date = parse_date(&captures)?;
+    let guard_id = captures[2].parse::<i32>()
+      .map_err(|_| Error::parse(captures.get(2).map_or(1, |m| m.start() + 1), format!("Unable to parse guard id {:?}", &captures[2])))?;
     
-    ParsedLine::WakeUp(date)
+    Ok(ParsedLine::ShiftStart(date, guard_id))
+  } else if let Some(captures) = ASLEEP_MATCHER.captures(line) {
+    Ok(ParsedLine::FallAsleep(parse_date(&captures)?))
+  } else if let Some(captures) = WAKE_UP_MATCHER.captures(line) {
+    Ok(ParsedLine::WakeUp(parse_date(&captures)?))
   } else {
-    panic!("Line does not match any known type: {}", line);
+    Err(Error::parse(1, format!("Line does not match any known type: {}", line)))
   }
 }
 
-fn read_guards_log(path: &str) -> GuardsLog {
-  let mut lines = read_input(path);
-  lines.sort_unstable();
+pub struct Day4;
+
+fn read_shift_log<'a, I: Iterator<Item = &'a str>>(lines: I) -> Result<ShiftLog> {
+  // Keep the original line numbers around, so parse errors still point at the input file
+  let mut lines: Vec<(usize, &str)> = lines.enumerate().collect();
+  lines.sort_unstable_by_key(|(_, l)| *l);
 
-  let mut log: GuardsLog = GuardsLog::new();
-  lines.iter()
-    .map(|l| parse_line(l))
-    .for_each(|e| log.handle_event(&e));
+  let events = lines.into_iter()
+    .map(|(i, line)| parse_line(line).map_err(|e| e.at_line(i + 1)))
+    .collect::<Result<Vec<ParsedLine>>>()?;
 
-  log
+  Ok(ShiftLog { events })
 }
 
 /// For this one it seems that the easiest solution is to sort the input chronologically
@@ -187,18 +232,22 @@ fn read_guards_log(path: &str) -> GuardsLog {
 /// Probably the last '60n' could be shaven-off if the intermediate representation 'GuardsLog'
 /// is ditched and the parser tracks guards to find the most-asleep one. However, this
 /// would require separate implementation of specialised parser for part 2
-pub fn solve_part1(path: &str) -> i32 {
-  let log = read_guards_log(path);
-
+fn solve_part1(log: &GuardsLog, trace: &Trace) -> Result<i64> {
   let (guard_id, guard_data) = log.guards.iter()
     .max_by_key(|i| i.1.minutes_asleep.iter().sum::<i32>())
-    .unwrap();
+    .ok_or_else(|| Error::NoSolution("There are no guards in the log".to_string()))?;
 
   let (guard_most_asleep_minute, _) = guard_data.minutes_asleep.iter().enumerate()
     .max_by_key(|e| e.1)
     .unwrap();
 
-  guard_id * (guard_most_asleep_minute as i32)
+  trace.emit(0, "sleepiest", || vec![
+    ("guard", (*guard_id).into()),
+    ("minutes", guard_data.minutes_asleep.iter().sum::<i32>().into()),
+    ("minute", guard_most_asleep_minute.into())
+  ]);
+
+  Ok(i64::from(*guard_id) * (guard_most_asleep_minute as i64))
 }
 
 /// All but the last steps are the same as in part1
@@ -207,15 +256,56 @@ pub fn solve_part1(path: &str) -> i32 {
 /// find the one with the largest value of times_asleep.
 /// 
 /// Time complexity is same as for part 1.
-pub fn solve_part2(path: &str) -> i32 {
-  let log = read_guards_log(path);
-
-  let (guard_id, guard_most_asleep_minute, _) = log.guards.iter().flat_map(|(guard_id, guard_data)| {
+fn solve_part2(log: &GuardsLog, trace: &Trace) -> Result<i64> {
+  let (guard_id, guard_most_asleep_minute, times_asleep) = log.guards.iter().flat_map(|(guard_id, guard_data)| {
     guard_data.minutes_asleep.iter().enumerate()
       .map(move |(guard_most_asleep_minute, times_asleep)| (guard_id, guard_most_asleep_minute, times_asleep))
   })
   .max_by_key(|e| e.2)
-  .unwrap();
+  .ok_or_else(|| Error::NoSolution("There are no guards in the log".to_string()))?;
+
+  trace.emit(0, "most-regular", || vec![
+    ("guard", (*guard_id).into()), ("minute", guard_most_asleep_minute.into()), ("times", (*times_asleep).into())
+  ]);
+  Ok(i64::from(*guard_id) * (guard_most_asleep_minute as i64))
+}
+
+impl Solver for Day4 {
+  type Input = ShiftLog;
+
+  fn parse(&self, input: &RawInput) -> Result<ShiftLog> {
+    read_shift_log(input.lines())
+  }
+
+  fn part1(&self, input: &ShiftLog, ctx: &Context) -> Result<Answer> {
+    Ok(solve_part1(&input.guards_log(&ctx.trace)?, &ctx.trace)?.into())
+  }
+
+  fn part2(&self, input: &ShiftLog, ctx: &Context) -> Result<Answer> {
+    Ok(solve_part2(&input.guards_log(&ctx.trace)?, &ctx.trace)?.into())
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
 
-  guard_id * (guard_most_asleep_minute as i32)
-}
\ No newline at end of file
+  #[test]
+  fn transitions_are_explained_only_when_tracing() {
+    let input = RawInput::from_text(
+      "[1518-11-01 00:05] falls asleep\n[1518-11-01 00:00] Guard #10 begins shift\n[1518-11-01 00:25] wakes up\n"
+    );
+    let log = Day4.parse(&input).expect("Unable to parse log");
+
+    let trace = Trace::default();
+    solve_part1(&log.guards_log(&trace).unwrap(), &trace).unwrap();
+    assert!(trace.events().is_empty());
+
+    let trace = Trace::enabled();
+    assert_eq!(solve_part1(&log.guards_log(&trace).unwrap(), &trace).unwrap(), 240);
+
+    let events: Vec<String> = trace.events().iter().map(|e| e.name.to_string()).collect();
+    assert_eq!(events, vec!["shift", "asleep", "awake", "sleepiest"]);
+    assert_eq!(trace.events()[1].fields, vec![("guard", 10.into()), ("at", "1518-11-01 00:05".into())]);
+  }
+}
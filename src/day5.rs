@@ -1,57 +1,77 @@
-use std::collections::VecDeque;
-use std::cmp::min;
+use std::convert::TryFrom;
 
-use crate::utils::read_input;
+use crate::error::{Error, Result};
+use crate::solver::{Answer, Context, Solver};
+use crate::trace::Trace;
+use crate::utils::RawInput;
 
-pub fn solve_part1(path: &str) -> usize {
-  let lines = read_input(path);
-  let polymer = lines.get(0).expect("Unable to read polymer");
+pub struct Day5;
 
-  reduce_polymer(polymer).len()
+fn solve_part1(polymer: &str, trace: &Trace) -> usize {
+  reduce_traced(polymer, trace).len()
 }
 
-pub fn solve_part2(path: &str) -> usize {
-  let lines = read_input(path);
-  let polymer = lines.get(0).expect("Unable to read polymer");
-
-  (b'A' .. b'Z')
+/// Only the length left after removing each unit is explained, the reductions of 26
+/// polymers would bury it
+fn solve_part2(polymer: &str, trace: &Trace) -> usize {
+  (b'A' ..= b'Z')
     .map(char::from)
-    .map(|unit|
-      polymer.chars().filter(|c|
+    .map(|unit| {
+      let cleaned_polymer: String = polymer.chars().filter(|c|
         !c.eq_ignore_ascii_case(&unit)
-      ).collect::<String>()
-    )
-    .map(|cleaned_polymer| reduce_polymer(&cleaned_polymer).len())
+      ).collect();
+
+      let length = reduce_polymer(&cleaned_polymer).len();
+      trace.emit(0, "remove", || vec![("unit", unit.to_string().into()), ("length", length.into())]);
+      length
+    })
     .min()
     .unwrap()
 }
 
-fn reduce_polymer(polymer: &str) -> String {
-  if polymer.is_empty() {
-    return String::new()
-  }
+/// Units are pushed on a stack, a unit reacting with the one on top removes both of them.
+/// Removing from the middle of a buffer made long polymers quadratic
+pub fn reduce_polymer(polymer: &str) -> String {
+  reduce_traced(polymer, &Trace::default())
+}
 
-  let mut buffer: VecDeque<char> = polymer.chars().collect();
-  let mut current_index = 0;
+/// Reduces the polymer, explaining every reaction with the position of the unit that
+/// triggered it
+fn reduce_traced(polymer: &str, trace: &Trace) -> String {
+  let mut stack: Vec<char> = Vec::with_capacity(polymer.len());
 
-  loop {
-    let mby_next_char = buffer.get(current_index + 1);
-    if mby_next_char.is_none() {
-      break buffer.iter().collect::<String>()
+  for (position, unit) in polymer.chars().enumerate() {
+    match stack.last() {
+      Some(top) if *top != unit && top.eq_ignore_ascii_case(&unit) => {
+        trace.emit(0, "react", || vec![("position", position.into()), ("units", format!("{}{}", top, unit).into())]);
+        stack.pop();
+      },
+      _ => stack.push(unit)
     }
-    let next_char: &char = mby_next_char.unwrap();
-    let current_char: &char = &buffer[current_index];
+  }
 
-    if *next_char != *current_char && next_char.to_ascii_lowercase() == current_char.to_ascii_lowercase() {
-      // Reduction!
-      buffer.remove(current_index + 1);
-      buffer.remove(current_index);
+  stack.into_iter().collect()
+}
+
+impl Solver for Day5 {
+  type Input = String;
+
+  fn parse(&self, input: &RawInput) -> Result<String> {
+    let polymer = input.single_line()?;
 
-      current_index = if current_index == 0 { 0 } else { current_index - 1};
-    } else {
-      current_index = min(current_index + 1, buffer.len() - 1);
+    match polymer.find(|c: char| !c.is_ascii_alphabetic()) {
+      Some(column) => Err(Error::parse(column + 1, "Polymer can only contain ASCII letters").at_line(1)),
+      None => Ok(polymer.to_string())
     }
   }
+
+  fn part1(&self, input: &String, ctx: &Context) -> Result<Answer> {
+    Answer::try_from(solve_part1(input, &ctx.trace))
+  }
+
+  fn part2(&self, input: &String, ctx: &Context) -> Result<Answer> {
+    Answer::try_from(solve_part2(input, &ctx.trace))
+  }
 }
 
 #[cfg(test)]
@@ -69,4 +89,22 @@ mod test {
     assert_eq!(reduce_polymer("c"), "c");
     assert_eq!(reduce_polymer("aBAb"), "aBAb");
   }
+
+  #[test]
+  fn reactions_are_explained() {
+    let trace = Trace::enabled();
+    assert_eq!(solve_part1("cBaAbd", &trace), 2);
+
+    let reactions: Vec<_> = trace.events().into_iter().map(|e| e.fields).collect();
+    assert_eq!(reactions, vec![
+      vec![("position", 3.into()), ("units", "aA".into())],
+      vec![("position", 4.into()), ("units", "Bb".into())]
+    ]);
+  }
+
+  #[test]
+  fn every_unit_type_is_removed() {
+    // Only removing the last unit type lets the rest of the polymer react away
+    assert_eq!(solve_part2("zAzaz", &Trace::default()), 0);
+  }
 }
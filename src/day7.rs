@@ -3,14 +3,21 @@ use std::str;
 use std::cmp::min;
 use regex::Regex;
 
-use crate::utils::read_input;
+use crate::cancel::Cancellation;
+use crate::error::{Error, Result};
+use crate::solver::{Answer, Context, Solver};
+use crate::trace::Trace;
+use crate::utils::RawInput;
+
+/// Steps are named A to Z, more workers than that would never have anything to do
+const MAX_WORKERS: usize = 26;
 
 lazy_static! {
   static ref LINE_PARSE: Regex = Regex::new("^Step ([A-Z]{1}) must be finished before step ([A-Z]{1}) can begin.$")
     .unwrap();
 }
 
-fn build_graph(dependencies: &[(u8, u8)]) -> HashMap<u8, HashSet<u8>> {
+pub fn build_graph(dependencies: &[(u8, u8)]) -> HashMap<u8, HashSet<u8>> {
   let mut result: HashMap<u8, HashSet<u8>> = HashMap::new();
   dependencies.iter().fold(&mut result, |acc, (before, after)| {
     let deps = acc.entry(*after).or_insert_with(HashSet::new);
@@ -36,82 +43,140 @@ fn resolve_next_step(dependencies: &HashMap<u8, HashSet<u8>>, completed: &[u8])
   doable.iter().cloned().nth(0)
 }
 
-fn resolve_order(dependencies: &mut HashMap<u8, HashSet<u8>>) -> String {
+fn step_names(steps: &[u8]) -> String {
+  steps.iter().map(|c| char::from(c + b'A' - 1)).collect()
+}
+
+fn cycle_error(dependencies: &HashMap<u8, HashSet<u8>>) -> Error {
+  let mut remaining: Vec<u8> = dependencies.keys().cloned().collect();
+  remaining.sort();
+
+  Error::Cycle(format!("Unable to determine next step, remaining steps: {}", step_names(&remaining)))
+}
+
+pub fn resolve_order(dependencies: &mut HashMap<u8, HashSet<u8>>, cancel: &Cancellation) -> Result<String> {
   let mut result: Vec<u8> = Vec::new();
 
   while !dependencies.is_empty() {
+    cancel.check()?;
     let next_step = resolve_next_step(dependencies, &result)
-      .unwrap_or_else(|| panic!("Unable to determine next step, result: {:?}, dependencies: {:?}", result, dependencies));
+      .ok_or_else(|| cycle_error(dependencies))?;
 
     dependencies.remove(&next_step);
     result.push(next_step);
   }
 
-  String::from_utf8(result.iter().map(|c| c + b'A' - 1).collect()).unwrap()
+  Ok(step_names(&result))
 }
 
-fn resolve_time(dependencies: &mut HashMap<u8, HashSet<u8>>, offset: u32, workers: usize) -> u32 {
+/// Every tick, a time at which a worker completes its step, is explained with the steps
+/// completed and assigned by the workers. The cancellation token is checked every tick
+pub fn resolve_time(
+  dependencies: &mut HashMap<u8, HashSet<u8>>, offset: u32, workers: usize, trace: &Trace, cancel: &Cancellation
+) -> Result<u32> {
+  if workers == 0 {
+    return Err(Error::InvalidInput("At least one worker is needed".to_string()));
+  }
+  if workers > MAX_WORKERS {
+    return Err(Error::InvalidInput(format!("At most {} workers can be given steps, got {}", MAX_WORKERS, workers)));
+  }
+
   let mut current_time = 0u32;
-  let mut next_time: u32;
   let mut completed: Vec<u8> = Vec::new();
   let mut workers: Vec<Option<(u8, u32)>> = vec![None; workers];
 
-  while !dependencies.is_empty() {    
-    next_time = std::u32::MAX;
+  while !dependencies.is_empty() {
+    cancel.check()?;
+    let mut next_time: Option<u32> = None;
+    trace.emit(0, "tick", || vec![("time", current_time.into())]);
 
-    for w in &mut workers {
+    for (worker, w) in workers.iter_mut().enumerate() {
       if let Some((task, completes_at)) = w {
         if *completes_at == current_time {
+          trace.emit(1, "complete", || vec![("worker", worker.into()), ("step", step_names(&[*task]).into())]);
           completed.push(*task);
           *w = None;
         } else {
-          next_time = min(next_time, *completes_at);
+          next_time = Some(next_time.map_or(*completes_at, |t| min(t, *completes_at)));
         }
       }
     }
 
-    for w in &mut workers {
+    for (worker, w) in workers.iter_mut().enumerate() {
       if w.is_none() {
         let possible_next_step = resolve_next_step(dependencies, &completed);
         if let Some(next_step) = possible_next_step {
           dependencies.remove(&next_step);
-          let completion_time = offset + u32::from(next_step) + current_time;
-          next_time = min(next_time, completion_time);
+          let completion_time = offset.checked_add(u32::from(next_step))
+            .and_then(|duration| duration.checked_add(current_time))
+            .ok_or_else(|| Error::InvalidInput(format!(
+              "Step {} started at {} with offset {} completes too late", step_names(&[next_step]), current_time, offset
+            )))?;
+          next_time = Some(next_time.map_or(completion_time, |t| min(t, completion_time)));
+          trace.emit(1, "assign", || vec![
+            ("worker", worker.into()), ("step", step_names(&[next_step]).into()), ("until", completion_time.into())
+          ]);
           *w = Some((next_step, completion_time));
         }
       }
     }
 
-    current_time = next_time;
+    // Nobody is working and nothing can be started, remaining steps wait on each other
+    current_time = next_time.ok_or_else(|| cycle_error(dependencies))?;
   }
 
-  current_time
+  trace.emit(0, "done", || vec![("time", current_time.into())]);
+  Ok(current_time)
 } 
 
-fn parse_dependency(line: &str) -> (u8, u8) {
-  let matches = LINE_PARSE.captures(line).unwrap_or_else(|| panic!("Unable to parse line: {}", line));
+pub fn parse_dependency(line: &str) -> Result<(u8, u8)> {
+  let matches = LINE_PARSE.captures(line)
+    .ok_or_else(|| Error::parse(1, format!("Unable to parse line: {}", line)))?;
  
-  (
-    matches.get(1).unwrap().as_str().as_bytes()[0] - b'A' + 1,
-    matches.get(2).unwrap().as_str().as_bytes()[0] - b'A' + 1
-  )
+  Ok((
+    matches[1].as_bytes()[0] - b'A' + 1,
+    matches[2].as_bytes()[0] - b'A' + 1
+  ))
 }
 
-fn load_dependencues(path: &str) -> Vec<(u8, u8)> {
-  read_input(path).iter().map(|line| parse_dependency(line)).collect()
+pub struct Day7;
+
+fn load_dependencues<'a, I: Iterator<Item = &'a str>>(lines: I) -> Result<Vec<(u8, u8)>> {
+  lines.enumerate()
+    .map(|(i, line)| parse_dependency(line).map_err(|e| e.at_line(i + 1)))
+    .collect()
 }
 
-pub fn solve_part1(path: &str) -> String {
-  let dependencies = load_dependencues(path);
-  let mut graph = build_graph(&dependencies);
-  resolve_order(&mut graph)
+fn solve_part1(dependencies: &[(u8, u8)], cancel: &Cancellation) -> Result<String> {
+  let mut graph = build_graph(dependencies);
+  resolve_order(&mut graph, cancel)
 }
 
-pub fn solve_part2(path: &str) -> u32 {
-  let dependencies = load_dependencues(path);
-  let mut graph = build_graph(&dependencies);
+/// Time needed by `workers` to complete all the steps, when step A takes `offset` + 1
+/// seconds, step B `offset` + 2 seconds and so on
+fn solve_part2(dependencies: &[(u8, u8)], offset: u32, workers: usize, ctx: &Context) -> Result<u32> {
+  let mut graph = build_graph(dependencies);
+
+  resolve_time(&mut graph, offset, workers, &ctx.trace, &ctx.cancel)
+}
+
+impl Solver for Day7 {
+  type Input = Vec<(u8, u8)>;
+
+  fn parse(&self, input: &RawInput) -> Result<Vec<(u8, u8)>> {
+    load_dependencues(input.lines())
+  }
+
+  fn part1(&self, input: &Vec<(u8, u8)>, ctx: &Context) -> Result<Answer> {
+    Ok(solve_part1(input, &ctx.cancel)?.into())
+  }
 
-  resolve_time(&mut graph, 60_u32, 5_usize)
+  fn part2(&self, input: &Vec<(u8, u8)>, ctx: &Context) -> Result<Answer> {
+    let offset = ctx.params.get_or("offset", 60_u32)?;
+    let workers = ctx.params.get_or("workers", 5_usize)?;
+
+    Ok(solve_part2(input, offset, workers, ctx)?.into())
+  }
 }
 
 #[cfg(test)]
@@ -120,6 +185,51 @@ mod test {
 
   #[test]
   fn test_line_parsing() {
-    assert_eq!(parse_dependency("Step A must be finished before step B can begin."), (1, 2))
+    assert_eq!(parse_dependency("Step A must be finished before step B can begin.").expect("Unable to parse line"), (1, 2))
+  }
+
+  #[test]
+  fn test_cycle_detection() {
+    let dependencies = vec![(1, 2), (2, 3), (3, 2)];
+
+    assert!(matches!(resolve_order(&mut build_graph(&dependencies), &Cancellation::new()), Err(Error::Cycle(_))));
+    assert!(matches!(
+      resolve_time(&mut build_graph(&dependencies), 0, 2, &Trace::default(), &Cancellation::new()), Err(Error::Cycle(_))
+    ));
+  }
+
+  #[test]
+  fn cancelled_steps_stop_resolving() {
+    let cancel = Cancellation::new();
+    cancel.cancel();
+
+    assert!(matches!(resolve_order(&mut build_graph(&[(1, 2)]), &cancel), Err(Error::Cancelled)));
+    assert!(matches!(resolve_time(&mut build_graph(&[(1, 2)]), 0, 2, &Trace::default(), &cancel), Err(Error::Cancelled)));
+  }
+
+  #[test]
+  fn workers_are_limited_to_the_number_of_steps() {
+    let resolve = |workers: usize| resolve_time(&mut build_graph(&[(1, 2)]), 0, workers, &Trace::default(), &Cancellation::new());
+
+    assert_eq!(resolve(MAX_WORKERS).unwrap(), 3);
+    assert!(matches!(resolve(MAX_WORKERS + 1), Err(Error::InvalidInput(_))));
+    assert!(matches!(resolve(usize::MAX), Err(Error::InvalidInput(_))));
+  }
+
+  #[test]
+  fn completion_times_past_the_last_one_are_rejected() {
+    let result = resolve_time(&mut build_graph(&[(1, 2)]), u32::MAX - 1, 2, &Trace::default(), &Cancellation::new());
+
+    assert!(matches!(result, Err(Error::InvalidInput(_))));
+  }
+
+  #[test]
+  fn worker_assignments_are_explained() {
+    let trace = Trace::enabled();
+    assert_eq!(resolve_time(&mut build_graph(&[(1, 2)]), 0, 2, &trace, &Cancellation::new()).unwrap(), 3);
+
+    let events: Vec<String> = trace.events().iter().map(|e| e.name.to_string()).collect();
+    assert_eq!(events, vec!["tick", "assign", "tick", "complete", "assign", "done"]);
+    assert_eq!(trace.events()[4].fields, vec![("worker", 0.into()), ("step", "B".into()), ("until", 3.into())]);
   }
 }
@@ -1,70 +1,142 @@
-use crate::utils::read_input;
+use std::convert::TryFrom;
 
-fn load_tree_list(path: &str) -> Vec<usize> {
-  let lines = read_input(path);
-  let input = lines.get(0)
-    .unwrap_or_else(|| panic!("Unable to read line from input file: {}", path));
+use crate::error::{Error, Result};
+use crate::solver::{Answer, Context, Solver};
+use crate::utils::RawInput;
 
-  parse_tree_list(input)
+pub struct Day8;
+
+/// License tree, kept in the same flat form it is serialized in: a header with number
+/// of children and number of metadata entries, followed by the children and metadata
+pub struct Tree {
+  pub nodes: Vec<usize>
+}
+
+impl Tree {
+  pub fn parse(input_line: &str) -> Result<Tree> {
+    Ok(Tree { nodes: parse_tree_list(input_line)? })
+  }
+
+  pub fn metadata_sum(&self) -> Result<usize> {
+    Ok(sum_node_metadata(&self.nodes, 0, 0)?.1)
+  }
+
+  pub fn root_value(&self) -> Result<usize> {
+    Ok(calc_node_value(&self.nodes, 0, 0)?.1)
+  }
+}
+
+fn load_tree(input: &RawInput) -> Result<Tree> {
+  Tree::parse(input.single_line()?).map_err(|e| e.at_line(1))
+}
+
+pub(crate) fn parse_tree_list(input_line: &str) -> Result<Vec<usize>> {
+  let mut column = 1;
+
+  input_line.split(' ').map(|s| {
+    let value = s.parse::<usize>()
+      .map_err(|e| Error::parse(column, format!("Unable to convert {:?} to number: {}", s, e)));
+    column += s.len() + 1;
+    value
+  }).collect()
+}
+
+/// Nodes are walked recursively, deeper trees are rejected before they overflow the stack
+const MAX_DEPTH: usize = 1000;
+
+fn truncated_tree() -> Error {
+  Error::InvalidInput("Tree ends before all of its nodes are read".to_string())
+}
+
+/// Sum of the values, metadata entries are read as `usize` so a sum of a few of them can overflow
+fn checked_sum<'a, I: Iterator<Item = &'a usize>>(mut values: I) -> Result<usize> {
+  values.try_fold(0_usize, |sum, value| sum.checked_add(*value))
+    .ok_or_else(|| Error::InvalidInput("Sum of the metadata entries is too large".to_string()))
+}
+
+fn check_depth(depth: usize) -> Result<()> {
+  if depth > MAX_DEPTH {
+    Err(Error::InvalidInput(format!("Tree is nested deeper than {} nodes", MAX_DEPTH)))
+  } else {
+    Ok(())
+  }
 }
 
-fn parse_tree_list(input_line: &str) -> Vec<usize> {
-  input_line.split(' ').map(|s| 
-    s.parse::<usize>().unwrap_or_else(|e| 
-      panic!("Unable to convert to number: {:?}", e))
-    ).collect()
+/// Reads header of the node starting at `start_index`, returns number of its
+/// children and metadata entries
+fn node_header(tree: &[usize], start_index: usize) -> Result<(usize, usize)> {
+  match tree.get(start_index .. start_index + 2) {
+    Some(header) => Ok((header[0], header[1])),
+    None => Err(truncated_tree())
+  }
+}
+
+fn node_metadata(tree: &[usize], start_index: usize, entries: usize) -> Result<&[usize]> {
+  start_index.checked_add(entries)
+    .and_then(|end_index| tree.get(start_index .. end_index))
+    .ok_or_else(truncated_tree)
 }
 
-fn sum_node_metadata(tree: &[usize], start_index: usize) -> (usize, usize) {
-  let mut metadata_sum = 0_usize;
-  let children = tree[start_index];
-  let metadata_entries = tree[start_index + 1];
+fn sum_node_metadata(tree: &[usize], start_index: usize, depth: usize) -> Result<(usize, usize)> {
+  check_depth(depth)?;
+  let (children, metadata_entries) = node_header(tree, start_index)?;
+  let mut children_sums: Vec<usize> = Vec::with_capacity(children.min(tree.len()));
   let mut next_index = start_index + 2;
 
   for _ in 0 .. children {
-    let (end_index, child_sum) = sum_node_metadata(tree, next_index);
-    metadata_sum += child_sum;
+    let (end_index, child_sum) = sum_node_metadata(tree, next_index, depth + 1)?;
+    children_sums.push(child_sum);
     next_index = end_index;
   }
 
-  metadata_sum += &tree[next_index .. next_index + metadata_entries].iter().sum();
-  
-  (next_index + metadata_entries, metadata_sum)
+  let metadata = node_metadata(tree, next_index, metadata_entries)?;
+  let metadata_sum = checked_sum(children_sums.iter().chain(metadata))?;
+
+  Ok((next_index + metadata_entries, metadata_sum))
 }
 
-fn calc_node_value(tree: &[usize], start_index: usize) -> (usize, usize) {
-  let mut node_value = 0_usize;
+fn calc_node_value(tree: &[usize], start_index: usize, depth: usize) -> Result<(usize, usize)> {
+  check_depth(depth)?;
 
-  let children_count = tree[start_index];
-  let metadata_count = tree[start_index + 1];
-  let mut children_values: Vec<usize> = Vec::with_capacity(children_count);
+  let (children_count, metadata_count) = node_header(tree, start_index)?;
+  let mut children_values: Vec<usize> = Vec::with_capacity(children_count.min(tree.len()));
   let mut next_index = start_index + 2;
 
   for _ in 0 .. children_count {
-    let (end_index, children_value) = calc_node_value(tree, next_index);
+    let (end_index, children_value) = calc_node_value(tree, next_index, depth + 1)?;
     children_values.push(children_value);
     next_index = end_index;
   }
 
-  if children_count == 0 {
-    node_value += &tree[next_index .. next_index + metadata_count].iter().sum();
+  let metadata = node_metadata(tree, next_index, metadata_count)?;
+  let node_value = if children_count == 0 {
+    checked_sum(metadata.iter())?
   } else {
-    node_value += &tree[next_index .. next_index + metadata_count].iter().filter_map(|index| {
-      children_values.get(*index - 1)
-    }).sum()
-  }
+    checked_sum(metadata.iter().filter_map(|index| {
+      index.checked_sub(1).and_then(|i| children_values.get(i))
+    }))?
+  };
 
-  (next_index + metadata_count, node_value)
+  Ok((next_index + metadata_count, node_value))
 }
 
-pub fn solve_part1(path: &str) -> usize {
-  let tree = load_tree_list(path);
-  sum_node_metadata(&tree, 0).1
-}
+impl Solver for Day8 {
+  type Input = Tree;
+
+  // Overflowing sums and values are errors since version 2
+  const VERSION: &'static str = "2";
+
+  fn parse(&self, input: &RawInput) -> Result<Tree> {
+    load_tree(input)
+  }
+
+  fn part1(&self, input: &Tree, _ctx: &Context) -> Result<Answer> {
+    Answer::try_from(input.metadata_sum()?)
+  }
 
-pub fn solve_part2(path: &str) -> usize {
-  let tree = load_tree_list(path);
-  calc_node_value(&tree, 0).1
+  fn part2(&self, input: &Tree, _ctx: &Context) -> Result<Answer> {
+    Answer::try_from(input.root_value()?)
+  }
 }
 
 #[cfg(test)]
@@ -74,8 +146,34 @@ mod test {
   #[test]
   fn test_input_parsing() {
     assert_eq!(
-      parse_tree_list("2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2"),
+      parse_tree_list("2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2").expect("Unable to parse tree"),
       vec!(2, 3, 0, 3, 10, 11, 12, 1, 1, 0, 1, 99, 2, 1, 1, 2)
     );
   }
+
+  #[test]
+  fn test_input_parsing_reports_column() {
+    match parse_tree_list("2 3 0 x 10") {
+      Err(Error::Parse { column, .. }) => assert_eq!(column, 7),
+      r => panic!("Expected parse error, got {:?}", r)
+    }
+  }
+
+  #[test]
+  fn test_truncated_tree() {
+    assert!(matches!(sum_node_metadata(&[1, 1, 0, 3, 10], 0, 0), Err(Error::InvalidInput(_))));
+    assert!(matches!(calc_node_value(&[1, 1, 0, 3, 10], 0, 0), Err(Error::InvalidInput(_))));
+  }
+
+  #[test]
+  fn deep_or_overflowing_trees_are_rejected() {
+    let deep: Vec<usize> = std::iter::repeat_n([1, 1], MAX_DEPTH + 1).flatten()
+      .chain([0, 1]).chain(std::iter::repeat_n(1, MAX_DEPTH + 2))
+      .collect();
+    let overflowing = Tree { nodes: vec![0, 2, usize::MAX, 1] };
+
+    assert!(matches!(Tree { nodes: deep }.metadata_sum(), Err(Error::InvalidInput(_))));
+    assert!(matches!(overflowing.metadata_sum(), Err(Error::InvalidInput(_))));
+    assert!(matches!(overflowing.root_value(), Err(Error::InvalidInput(_))));
+  }
 }
\ No newline at end of file
@@ -1,9 +1,104 @@
 use std::iter::repeat_with;
-use std::cell::RefCell;
-use std::rc::Rc;
-use std::cmp::Eq;
+use std::convert::TryFrom;
+use regex::Regex;
 
-pub fn solve_part1(players: usize, last_marble: u32) -> u32 {
+use crate::cancel::Cancellation;
+use crate::error::{Error, Result};
+use crate::solver::{Answer, Context, Solver, Strategy};
+use crate::utils::RawInput;
+
+lazy_static! {
+  static ref GAME_MATCHER: Regex = Regex::new("^([0-9]+) players; last marble is worth ([0-9]+) points$")
+    .unwrap();
+}
+
+pub struct Day9;
+
+/// Largest game that is played, the circle needs memory for every marble
+const MAX_MARBLES: u32 = 20_000_000;
+const MAX_PLAYERS: usize = 1_000_000;
+/// Inserting into a `Vec` is quadratic, larger games are played on the linked list by default
+const VEC_MARBLES: u32 = 100_000;
+/// Marbles played between checks of the cancellation token
+const CANCEL_INTERVAL: u32 = 1 << 12;
+
+/// Parameters of the marble game
+pub struct Game {
+  pub players: usize,
+  pub last_marble: u32
+}
+
+impl Game {
+  pub fn new(players: usize, last_marble: u32) -> Game {
+    Game { players, last_marble }
+  }
+
+  /// Checks that the game can actually be played
+  pub fn validate(&self) -> Result<()> {
+    if self.players == 0 {
+      Err(Error::InvalidInput("Game needs at least one player".to_string()))
+    } else if self.last_marble == 0 {
+      Err(Error::InvalidInput("Last marble has to be worth at least one point".to_string()))
+    } else if self.players > MAX_PLAYERS {
+      Err(Error::InvalidInput(format!("Game can have at most {} players, got {}", MAX_PLAYERS, self.players)))
+    } else if self.last_marble > MAX_MARBLES {
+      Err(Error::InvalidInput(format!("Last marble can be worth at most {} points, got {}", MAX_MARBLES, self.last_marble)))
+    } else {
+      Ok(())
+    }
+  }
+
+  /// Game from the input, with `players` and `last_marble` parameters taking precedence
+  fn with_params(&self, ctx: &Context) -> Result<Game> {
+    Ok(Game::new(
+      ctx.params.get_or("players", self.players)?,
+      ctx.params.get_or("last_marble", self.last_marble)?
+    ))
+  }
+
+  /// Validated game of the part, the second part is the same game only with the last
+  /// marble 100 times larger
+  fn for_part(&self, part: u8, ctx: &Context) -> Result<Game> {
+    let game = self.with_params(ctx)?;
+    let last_marble = match part {
+      1 => game.last_marble,
+      _ => game.last_marble.checked_mul(100)
+        .ok_or_else(|| Error::InvalidInput(format!("Last marble {} is too large", game.last_marble)))?
+    };
+    let game = Game::new(game.players, last_marble);
+    game.validate()?;
+
+    Ok(game)
+  }
+
+  /// Highest score of all the players once the last marble is played
+  pub fn high_score(&self) -> Result<u64> {
+    self.validate()?;
+    solve_part2(self.players, self.last_marble, &Cancellation::new())
+  }
+}
+
+/// Reads the game parameters, number of players and value of the last marble,
+/// from the puzzle input
+fn read_game(input: &RawInput) -> Result<Game> {
+  let line = input.single_line()?;
+  let captures = GAME_MATCHER.captures(line)
+    .ok_or_else(|| Error::parse(1, format!("Unable to parse game: {}", line)).at_line(1))?;
+  let parse_error = |group: usize, field: &str| {
+    let value = captures.get(group).unwrap();
+    Error::parse(value.start() + 1, format!("Unable to parse {} {:?}", field, value.as_str())).at_line(1)
+  };
+
+  let game = Game {
+    players: captures[1].parse::<usize>().map_err(|_| parse_error(1, "players"))?,
+    last_marble: captures[2].parse::<u32>().map_err(|_| parse_error(2, "last marble"))?
+  };
+  game.validate()?;
+
+  Ok(game)
+}
+
+fn solve_part1(players: usize, last_marble: u32, cancel: &Cancellation) -> Result<u64> {
   let mut next_id = 1_u32;
   let marbles = repeat_with(|| { 
     let current = next_id;
@@ -13,17 +108,21 @@ pub fn solve_part1(players: usize, last_marble: u32) -> u32 {
 
   let mut circle: Vec<u32> = vec![0];
   let mut current_marble_position = 0_usize;
-  let mut players = vec!(0u32; players);
+  let mut players = vec!(0u64; players);
 
   for (round, new_marble) in marbles.enumerate() {
     let current_player = round % players.len();
+    if new_marble % CANCEL_INTERVAL == 0 {
+      cancel.check()?;
+    }
+
     if new_marble % 23 == 0 {
       let removed_marble_position = if current_marble_position < 7 {
         circle.len() + current_marble_position - 7
       } else {
         current_marble_position - 7
       };
-      players[current_player] += new_marble + circle[removed_marble_position];
+      players[current_player] += u64::from(new_marble) + u64::from(circle[removed_marble_position]);
       circle.remove(removed_marble_position);
       current_marble_position = if removed_marble_position >= circle.len() {
         0
@@ -42,123 +141,84 @@ pub fn solve_part1(players: usize, last_marble: u32) -> u32 {
     }
   }
 
-  *players.iter().max().unwrap()
+  Ok(*players.iter().max().unwrap())
 }
 
-#[derive(PartialEq, Eq)]
+/// A single marble of the circle. Marbles are stored in an arena owned by
+/// `Circle` and linked by their arena indexes, so the circle has no reference
+/// cycles to leak and nothing has to be initialised before it exists.
 struct Marble {
   value: u32,
-  next: Rc<RefCell<Marble>>,
-  prev: Rc<RefCell<Marble>>,
+  next: usize,
+  prev: usize,
 }
 
-impl Marble {
-  fn new_circle() -> Rc<RefCell<Marble>> {
-    let first = Rc::new(RefCell::new(Marble {
-      value: 0,
-      next: unsafe { std::mem::zeroed() },
-      prev: unsafe { std::mem::zeroed() }
-    }));
-
-    std::mem::forget(
-      std::mem::replace(&mut first.borrow_mut().next, first.clone())
-    );
-    std::mem::forget(
-      std::mem::replace(&mut first.borrow_mut().prev, first.clone())
-    );
-    
-    first
+struct Circle {
+  marbles: Vec<Marble>
+}
+
+impl Circle {
+  fn new(capacity: usize) -> Circle {
+    let mut marbles = Vec::with_capacity(capacity);
+    marbles.push(Marble { value: 0, next: 0, prev: 0 });
+
+    Circle { marbles }
   }
 
-  fn move_cw(&self, n: usize) -> Rc<RefCell<Marble>> {
-    if n == 0 {
-      self.next.borrow().move_ccw(1)
-    } else if n == 1 {
-      self.next.clone()
-    } else {
-      self.next.borrow().move_cw(n - 1)
-    }
+  fn move_cw(&self, from: usize, n: usize) -> usize {
+    (0 .. n).fold(from, |i, _| self.marbles[i].next)
   }
 
-  fn move_ccw(&self, n: usize) -> Rc<RefCell<Marble>> {
-    if n == 0 {
-      self.prev.borrow().move_cw(1)
-    } else if n == 1 {
-      self.prev.clone()
-    } else {
-      self.prev.borrow().move_ccw(n - 1)
-    }
+  fn move_ccw(&self, from: usize, n: usize) -> usize {
+    (0 .. n).fold(from, |i, _| self.marbles[i].prev)
   }
 
-  fn append_after(&mut self, marble_value: u32) -> Rc<RefCell<Marble>> {
-    let new_prev_ref = { 
-      self.next.try_borrow()
-        .map(|n| n.prev.clone())
-        .ok()
-        .unwrap_or_else(|| self.prev.clone())
-    };
-    let new_next_ref = self.next.clone();
+  fn value(&self, at: usize) -> u32 {
+    self.marbles[at].value
+  }
 
-    let new_marble = Rc::new(RefCell::new(Marble {
+  fn append_after(&mut self, at: usize, marble_value: u32) -> usize {
+    let new_index = self.marbles.len();
+    let next = self.marbles[at].next;
+
+    self.marbles.push(Marble {
       value: marble_value,
-      next: new_next_ref,
-      prev: new_prev_ref
-    }));
-    
-    {
-      let mut next_b = self.next.try_borrow_mut();
-      match next_b.as_mut() {
-        Ok(n) => { n.prev = new_marble.clone() },
-        Err(_) => { self.prev = new_marble.clone() }
-      };
-    }
+      next,
+      prev: at
+    });
+    self.marbles[next].prev = new_index;
+    self.marbles[at].next = new_index;
 
-    self.next = new_marble.clone();
-    new_marble
+    new_index
   }
 
-  fn remove(&mut self) -> Rc<RefCell<Marble>> {
-    { self.prev.borrow_mut().next = self.next.clone(); }
-    { self.next.borrow_mut().prev = self.prev.clone(); }
+  fn remove(&mut self, at: usize) -> usize {
+    let Marble { next, prev, .. } = self.marbles[at];
+    self.marbles[prev].next = next;
+    self.marbles[next].prev = prev;
 
-    self.next.clone()
+    next
   }
 
-  fn _circle(&self) -> Vec<u32> {
-    let mut result = vec![self.value];
-    
-    let mut node = self.next.clone();
-    loop {
-      node = {
-        let c = node.borrow();
-        if c.value == self.value {
-          break;
-        }
-        result.push(c.value);
-        c.next.clone()
-      }
+  fn _circle(&self, from: usize) -> Vec<u32> {
+    let mut result = vec![self.value(from)];
+
+    let mut node = self.marbles[from].next;
+    while node != from {
+      result.push(self.value(node));
+      node = self.marbles[node].next;
     }
 
     result
   }
 
-  fn is_single(&self) -> bool {
-    self.value == { self.next.borrow().value }
-  }
+  fn _circle_rev(&self, from: usize) -> Vec<u32> {
+    let mut result = vec![self.value(from)];
 
-  fn _circle_rev(&self) -> Vec<u32> {
-    let mut result = vec![self.value];
-    
-    let mut node = self.prev.clone();
-    loop {
-      node = {
-        let c = node.borrow();
-        if c.value == self.value {
-          break;
-        }
-        result.push(c.value);
-        c.prev.clone()
-      }
+    let mut node = self.marbles[from].prev;
+    while node != from {
+      result.push(self.value(node));
+      node = self.marbles[node].prev;
     }
 
     result
@@ -168,8 +228,10 @@ impl Marble {
 /// Wow... this was quite a lesson.
 /// 
 /// Definetly shows how some of the borrow semantics in Rust make
-/// some things really, really difficult.
-pub fn solve_part2(players: usize, last_marble: u32) -> u32 {
+/// some things really, really difficult. The first take linked
+/// `Rc<RefCell<Marble>>` nodes directly; keeping marbles in an arena
+/// and linking them by index ended up both simpler and faster.
+fn solve_part2(players: usize, last_marble: u32, cancel: &Cancellation) -> Result<u64> {
   let mut next_id = 1_u32;
   let marbles = repeat_with(|| { 
     let current = next_id;
@@ -177,34 +239,24 @@ pub fn solve_part2(players: usize, last_marble: u32) -> u32 {
     current
   });
 
-  let mut current_marble = Marble::new_circle();
-  let mut players = vec!(0u32; players);
+  let mut circle = Circle::new(last_marble as usize + 1);
+  let mut current_marble = 0_usize;
+  let mut players = vec!(0u64; players);
 
 
   for (round, new_marble) in marbles.enumerate() {
     let current_player = round % players.len();
-    
+    if new_marble % CANCEL_INTERVAL == 0 {
+      cancel.check()?;
+    }
+
     if new_marble % 23 == 0 {
-      current_marble = {
-        let to_rem = { current_marble.borrow().move_ccw(7) };
-        let mut r = to_rem.borrow_mut();
-        players[current_player] += new_marble + r.value;
-        r.remove()
-      };
-    } else if current_marble.borrow().is_single() {
-      current_marble = {
-        let mut c = current_marble.borrow_mut();
-        c.append_after(new_marble)
-      };
+      let to_rem = circle.move_ccw(current_marble, 7);
+      players[current_player] += u64::from(new_marble) + u64::from(circle.value(to_rem));
+      current_marble = circle.remove(to_rem);
     } else {
-      current_marble = {
-        let next = {
-          let c = current_marble.borrow();
-          c.move_cw(1)
-        };
-        let mut n = next.borrow_mut();
-        n.append_after(new_marble)
-      };
+      let next = circle.move_cw(current_marble, 1);
+      current_marble = circle.append_after(next, new_marble);
     }
     
     if new_marble == last_marble {
@@ -212,31 +264,94 @@ pub fn solve_part2(players: usize, last_marble: u32) -> u32 {
     }
   }
 
-  *players.iter().max().unwrap()
+  Ok(*players.iter().max().unwrap())
+}
+
+fn vec_score(game: &Game, ctx: &Context) -> Result<Answer> {
+  Answer::try_from(solve_part1(game.players, game.last_marble, &ctx.cancel)?)
+}
+
+fn linked_list_score(game: &Game, ctx: &Context) -> Result<Answer> {
+  Answer::try_from(solve_part2(game.players, game.last_marble, &ctx.cancel)?)
+}
+
+impl Solver for Day9 {
+  type Input = Game;
+
+  // Overflowing scores are errors since version 2
+  const VERSION: &'static str = "2";
+
+  fn parse(&self, input: &RawInput) -> Result<Game> {
+    read_game(input)
+  }
+
+  fn part1(&self, input: &Game, ctx: &Context) -> Result<Answer> {
+    let game = input.for_part(1, ctx)?;
+
+    if game.last_marble <= VEC_MARBLES { vec_score(&game, ctx) } else { linked_list_score(&game, ctx) }
+  }
+
+  fn part2(&self, input: &Game, ctx: &Context) -> Result<Answer> {
+    linked_list_score(&input.for_part(2, ctx)?, ctx)
+  }
+
+  /// Inserting into a `Vec` is fine for the small first game, the second one needs the
+  /// linked list. Both are kept for the first game to compare them, the `Vec` would take
+  /// hours on the second one
+  fn strategies(&self, part: u8) -> Vec<Strategy<Game>> {
+    match part {
+      1 => vec![
+        Strategy { name: "vec", solve: |input, ctx| vec_score(&input.for_part(1, ctx)?, ctx) },
+        Strategy { name: "linked-list", solve: |input, ctx| linked_list_score(&input.for_part(1, ctx)?, ctx) }
+      ],
+      _ => vec![
+        Strategy { name: "linked-list", solve: |input, ctx| linked_list_score(&input.for_part(2, ctx)?, ctx) }
+      ]
+    }
+  }
 }
 
 #[cfg(test)]
 mod test {
   use super::*;
+  use crate::solver::Params;
 
   #[test]
   fn test_solution_part1() {
-    assert_eq!(solve_part1(9, 25), 32);
-    assert_eq!(solve_part1(10, 1618), 8317);
-    assert_eq!(solve_part1(13, 7999), 146373);
-    assert_eq!(solve_part1(17, 1104), 2764);
-    assert_eq!(solve_part1(21, 6111), 54718);
-    assert_eq!(solve_part1(30, 5807), 37305);
+    assert_eq!(solve_part1(9, 25, &Cancellation::new()).unwrap(), 32);
+    assert_eq!(solve_part1(10, 1618, &Cancellation::new()).unwrap(), 8317);
+    assert_eq!(solve_part1(13, 7999, &Cancellation::new()).unwrap(), 146373);
+    assert_eq!(solve_part1(17, 1104, &Cancellation::new()).unwrap(), 2764);
+    assert_eq!(solve_part1(21, 6111, &Cancellation::new()).unwrap(), 54718);
+    assert_eq!(solve_part1(30, 5807, &Cancellation::new()).unwrap(), 37305);
   }
 
   #[test]
   fn test_solution_part2() {
-    assert_eq!(solve_part2(9, 25), 32);
-    assert_eq!(solve_part2(10, 1618), 8317);
-    assert_eq!(solve_part2(13, 7999), 146373);
-    assert_eq!(solve_part2(17, 1104), 2764);
-    assert_eq!(solve_part2(21, 6111), 54718);
-    assert_eq!(solve_part2(30, 5807), 37305);
+    assert_eq!(solve_part2(9, 25, &Cancellation::new()).unwrap(), 32);
+    assert_eq!(solve_part2(10, 1618, &Cancellation::new()).unwrap(), 8317);
+    assert_eq!(solve_part2(13, 7999, &Cancellation::new()).unwrap(), 146373);
+    assert_eq!(solve_part2(17, 1104, &Cancellation::new()).unwrap(), 2764);
+    assert_eq!(solve_part2(21, 6111, &Cancellation::new()).unwrap(), 54718);
+    assert_eq!(solve_part2(30, 5807, &Cancellation::new()).unwrap(), 37305);
+  }
+
+  #[test]
+  fn params_override_game_from_input() {
+    let mut params = Params::new();
+    params.set("last_marble", 1618);
+    let game = Game::new(10, 25).with_params(&Context::with_params(params)).unwrap();
+
+    assert_eq!((game.players, game.last_marble), (10, 1618));
+  }
+
+  #[test]
+  fn cancelled_games_stop() {
+    let cancel = Cancellation::new();
+    cancel.cancel();
+
+    assert!(matches!(solve_part1(10, 20_000, &cancel), Err(Error::Cancelled)));
+    assert!(matches!(solve_part2(10, 20_000, &cancel), Err(Error::Cancelled)));
   }
 
 }
\ No newline at end of file
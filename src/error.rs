@@ -0,0 +1,85 @@
+use std::fmt;
+use std::io;
+use std::result;
+
+/// Errors produced while loading, parsing or solving the puzzles
+#[derive(Debug)]
+pub enum Error {
+  /// Puzzle input couldn't be read
+  Io { path: String, source: io::Error },
+  /// Puzzle input couldn't be parsed, `line` and `column` are 1-based, zero when unknown
+  Parse { file: Option<String>, line: usize, column: usize, message: String },
+  /// Input was parsed, but it doesn't describe a valid puzzle
+  InvalidInput(String),
+  /// Input is valid, but the puzzle has no solution for it
+  NoSolution(String),
+  /// Dependencies of the puzzle form a cycle, so they can't be resolved
+  Cycle(String),
+  /// Solving stopped early because its cancellation token was cancelled
+  Cancelled,
+  /// Request to the puzzle server failed, `status` is missing when no response was received
+  Http { url: String, status: Option<u16>, message: String }
+}
+
+pub type Result<T> = result::Result<T, Error>;
+
+impl Error {
+  pub fn parse<S: Into<String>>(column: usize, message: S) -> Error {
+    Error::Parse { file: None, line: 0, column, message: message.into() }
+  }
+
+  /// Sets the line of a parse error, other errors are returned unchanged
+  pub fn at_line(self, line_number: usize) -> Error {
+    match self {
+      Error::Parse { file, column, message, .. } => Error::Parse { file, line: line_number, column, message },
+      e => e
+    }
+  }
+
+  /// Sets the file of a parse error, other errors are returned unchanged
+  pub fn in_file(self, path: &str) -> Error {
+    match self {
+      Error::Parse { line, column, message, .. } => Error::Parse { file: Some(path.to_string()), line, column, message },
+      e => e
+    }
+  }
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Error::Io { path, source } => write!(f, "Unable to read {}: {}", path, source),
+      Error::Parse { file, line, column, message } => write!(
+        f, "{}:{}:{}: {}",
+        file.as_ref().map_or("<input>", String::as_str), line, column, message
+      ),
+      Error::InvalidInput(message) => write!(f, "Invalid input: {}", message),
+      Error::NoSolution(message) => write!(f, "No solution: {}", message),
+      Error::Cycle(message) => write!(f, "Cycle detected: {}", message),
+      Error::Cancelled => write!(f, "Cancelled"),
+      Error::Http { url, status: Some(status), message } => write!(f, "Unable to fetch {}: HTTP {}: {}", url, status, message),
+      Error::Http { url, status: None, message } => write!(f, "Unable to fetch {}: {}", url, message)
+    }
+  }
+}
+
+impl std::error::Error for Error {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      Error::Io { source, .. } => Some(source),
+      _ => None
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn parse_error_displays_location() {
+    let error = Error::parse(5, "Unexpected character").at_line(3).in_file("data/day1/input.txt");
+
+    assert_eq!(error.to_string(), "data/day1/input.txt:3:5: Unexpected character");
+  }
+}
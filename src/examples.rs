@@ -0,0 +1,102 @@
+use std::fs;
+use std::path::Path;
+use serde::Deserialize;
+
+use crate::error::{Error, Result};
+use crate::registry;
+use crate::solver::{Answer, Params};
+use crate::utils::read_toml;
+use crate::verify::{verify_input, Check, Expected};
+
+pub use crate::config::DEFAULT_DATA_DIR;
+
+/// Suffix of the sidecar file holding expected answers of an example input. An example
+/// `data/dayN/<name>.txt` is checked against `data/dayN/<name>.expected.toml`
+pub const SIDECAR_SUFFIX: &str = ".expected.toml";
+
+/// Contents of the sidecar file, answers are optional since some examples
+/// only apply to one of the parts
+#[derive(Debug, Deserialize)]
+struct Sidecar {
+  part1: Option<Answer>,
+  part2: Option<Answer>,
+  #[serde(default)]
+  params: Params
+}
+
+/// Example input of a day together with its expected answers
+#[derive(Debug)]
+pub struct Example {
+  pub day: u8,
+  pub expected: Expected
+}
+
+fn read_dir(path: &Path) -> Result<Vec<fs::DirEntry>> {
+  let io_error = |source| Error::Io { path: path.display().to_string(), source };
+  let mut entries = fs::read_dir(path).map_err(io_error)?
+    .collect::<std::io::Result<Vec<fs::DirEntry>>>()
+    .map_err(io_error)?;
+
+  entries.sort_by_key(|e| e.file_name());
+  Ok(entries)
+}
+
+fn day_examples(day: u8, day_dir: &Path) -> Result<Vec<Example>> {
+  let mut examples = Vec::new();
+
+  for entry in read_dir(day_dir)? {
+    let file_name = entry.file_name().to_string_lossy().to_string();
+    if let Some(name) = file_name.strip_suffix(SIDECAR_SUFFIX) {
+      let sidecar: Sidecar = read_toml(&entry.path().to_string_lossy())?;
+      let input = day_dir.join(format!("{}.txt", name));
+
+      if !input.is_file() {
+        return Err(Error::InvalidInput(format!("{} has no matching input {}", entry.path().display(), input.display())));
+      }
+
+      examples.push(Example {
+        day,
+        expected: Expected {
+          input: input.to_string_lossy().to_string(),
+          part1: sidecar.part1,
+          part2: sidecar.part2,
+          params: sidecar.params
+        }
+      });
+    }
+  }
+
+  Ok(examples)
+}
+
+/// Finds all the examples with a sidecar in `dayN` directories of the data directory
+pub fn discover(data_dir: &str) -> Result<Vec<Example>> {
+  let mut examples = Vec::new();
+
+  for entry in read_dir(Path::new(data_dir))? {
+    let day = entry.file_name().to_str()
+      .and_then(|name| name.strip_prefix("day"))
+      .and_then(|day| day.parse::<u8>().ok());
+
+    if let Some(day) = day {
+      examples.append(&mut day_examples(day, &entry.path())?);
+    }
+  }
+
+  examples.sort_by_key(|e| e.day);
+  Ok(examples)
+}
+
+/// Finds the examples with a sidecar in the `dayN` directory of a single day
+pub fn discover_day(data_dir: &str, day: u8) -> Result<Vec<Example>> {
+  let day_dir = Path::new(data_dir).join(format!("day{}", day));
+
+  if day_dir.is_dir() { day_examples(day, &day_dir) } else { Ok(Vec::new()) }
+}
+
+/// Runs the example against every part it has an answer for. Examples of days
+/// that are not linked into the binary are skipped
+pub fn run(example: &Example) -> Vec<Check> {
+  registry::find(example.day)
+    .map_or_else(Vec::new, |day| verify_input(&day, &example.expected))
+}
@@ -0,0 +1,118 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::error::{Error, Result};
+
+/// Environment variable with the session token, takes precedence over `session` in the config
+pub const SESSION_ENV: &str = "AOC_SESSION";
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+pub(crate) const USER_AGENT: &str = concat!("rust-advent-of-code-2018/", env!("CARGO_PKG_VERSION"));
+
+/// Where the input of a day came from
+#[derive(Debug, PartialEq)]
+pub enum Fetched {
+  /// Input was already on disk, nothing was downloaded
+  Cached(String),
+  /// Input was downloaded and saved to the path, with the given size in bytes
+  Downloaded(String, usize)
+}
+
+/// Session token from the environment, or from the config when the environment has none
+pub fn session_token(config: &Config) -> Option<String> {
+  env::var(SESSION_ENV).ok()
+    .filter(|token| !token.trim().is_empty())
+    .or_else(|| config.session.clone())
+}
+
+/// Inputs are cached once their file exists and isn't empty
+pub fn is_cached(path: &str) -> bool {
+  fs::metadata(path).is_ok_and(|m| m.len() > 0)
+}
+
+pub fn input_url(base_url: &str, day: u8) -> String {
+  format!("{}/day/{}/input", base_url.trim_end_matches('/'), day)
+}
+
+/// HTTP client shared by all the requests to the puzzle server
+pub(crate) fn agent() -> ureq::Agent {
+  ureq::AgentBuilder::new().timeout(TIMEOUT).build()
+}
+
+/// Error for a request that couldn't be made because no session token is set
+pub(crate) fn missing_session(url: &str) -> Error {
+  Error::Http {
+    url: url.to_string(),
+    status: None,
+    message: format!("no session token, set {} or session in the config", SESSION_ENV)
+  }
+}
+
+fn status_message(status: u16, body: &str) -> String {
+  let hint = match status {
+    400 | 401 | 403 => "session token was rejected, it may have expired",
+    404 => "puzzle is not unlocked yet, or the base URL is wrong",
+    500 ..= 599 => "server error, try again later",
+    _ => "unexpected response"
+  };
+
+  match body.lines().next().map(str::trim) {
+    Some(first_line) if !first_line.is_empty() => format!("{} ({})", hint, first_line),
+    _ => hint.to_string()
+  }
+}
+
+fn download(url: &str, session: &str) -> Result<String> {
+  let http_error = |status: Option<u16>, message: String| Error::Http { url: url.to_string(), status, message };
+  let response = agent()
+    .get(url)
+    .set("Cookie", &format!("session={}", session.trim()))
+    .set("User-Agent", USER_AGENT)
+    .call();
+
+  match response {
+    Ok(response) => {
+      let body = response.into_string().map_err(|e| http_error(None, e.to_string()))?;
+      if body.trim().is_empty() {
+        return Err(http_error(Some(200), "server returned an empty input".to_string()));
+      }
+      Ok(body)
+    },
+    Err(ureq::Error::Status(status, response)) => {
+      let body = response.into_string().unwrap_or_default();
+      Err(http_error(Some(status), status_message(status, &body)))
+    },
+    Err(ureq::Error::Transport(e)) => Err(http_error(None, e.to_string()))
+  }
+}
+
+/// Saves the input through a temporary file, so a failed write never leaves a partial input
+/// that would be taken for a cached one
+fn save(path: &str, content: &str) -> Result<()> {
+  let io_error = |source| Error::Io { path: path.to_string(), source };
+  let temporary = format!("{}.part", path);
+
+  if let Some(parent) = Path::new(path).parent() {
+    fs::create_dir_all(parent).map_err(io_error)?;
+  }
+  fs::write(&temporary, content).map_err(io_error)?;
+  fs::rename(&temporary, path).map_err(io_error)
+}
+
+/// Downloads the input of the day into the configured data directory, unless it's already there
+pub fn fetch(day: u8, config: &Config, session: Option<&str>) -> Result<Fetched> {
+  let path = config.input(day);
+  if is_cached(&path) {
+    return Ok(Fetched::Cached(path));
+  }
+
+  let url = input_url(&config.base_url, day);
+  let session = session.ok_or_else(|| missing_session(&url))?;
+  let content = download(&url, session)?;
+  save(&path, &content)?;
+
+  Ok(Fetched::Downloaded(path, content.len()))
+}
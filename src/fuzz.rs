@@ -0,0 +1,49 @@
+use crate::error::{Error, Result};
+use crate::registry;
+use crate::solver::{Answer, Context};
+use crate::utils::RawInput;
+
+// Entry points for fuzzing the parsers and the solvers with arbitrary bytes, used by the
+// targets of the `fuzz` crate. Whatever the bytes are, each of them has to return a value
+// or an error, without panicking or running for ever
+
+#[cfg(any(feature = "day3", feature = "day4", feature = "day7", feature = "day8"))]
+fn text(data: &[u8]) -> Result<&str> {
+  std::str::from_utf8(data).map_err(|e| Error::InvalidInput(format!("Input is not valid UTF-8: {}", e)))
+}
+
+/// Parses the bytes as the whole input of the day
+pub fn parse(day: u8, data: &[u8]) -> Result<()> {
+  let day = registry::find(day).ok_or_else(|| Error::InvalidInput(format!("Day {} is not registered", day)))?;
+
+  day.solver.parse_input(&RawInput::from_bytes(data)?).map(|_| ())
+}
+
+/// Parses the bytes as the input of the day and solves both of the parts with the
+/// default context, the second part is solved even when the first one fails
+pub fn solve(day: u8, data: &[u8]) -> Result<Vec<Result<Answer>>> {
+  let day = registry::find(day).ok_or_else(|| Error::InvalidInput(format!("Day {} is not registered", day)))?;
+  let input = day.solver.parse_input(&RawInput::from_bytes(data)?)?;
+
+  Ok([1, 2].iter().map(|part| day.solver.solve(*part, &*input, &Context::default())).collect())
+}
+
+#[cfg(feature = "day3")]
+pub fn claim(data: &[u8]) -> Result<()> {
+  crate::day3::Claim::from_input(text(data)?).map(|_| ())
+}
+
+#[cfg(feature = "day4")]
+pub fn guard_event(data: &[u8]) -> Result<()> {
+  crate::day4::parse_line(text(data)?).map(|_| ())
+}
+
+#[cfg(feature = "day7")]
+pub fn dependency(data: &[u8]) -> Result<()> {
+  crate::day7::parse_dependency(text(data)?).map(|_| ())
+}
+
+#[cfg(feature = "day8")]
+pub fn tree(data: &[u8]) -> Result<()> {
+  crate::day8::parse_tree_list(text(data)?).map(|_| ())
+}
@@ -0,0 +1,470 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::convert::TryFrom;
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+
+use crate::error::{Error, Result};
+use crate::examples::SIDECAR_SUFFIX;
+use crate::solver::Answer;
+
+/// Days that have an input generator
+pub const GENERATED_DAYS: [u8; 8] = [1, 2, 3, 4, 5, 7, 8, 9];
+
+/// Small, seedable pseudo-random generator (SplitMix64). Generated inputs have to be the
+/// same for the same seed on every platform and version, so no external crate is used
+pub struct Rng {
+  state: u64
+}
+
+impl Rng {
+  pub fn new(seed: u64) -> Rng {
+    Rng { state: seed }
+  }
+
+  pub fn next_u64(&mut self) -> u64 {
+    self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = self.state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+  }
+
+  /// Number in `0 .. n`, `n` has to be positive
+  pub fn below(&mut self, n: usize) -> usize {
+    (self.next_u64() % n as u64) as usize
+  }
+
+  /// Number in `low ..= high`
+  pub fn between(&mut self, low: i64, high: i64) -> i64 {
+    low + (self.next_u64() % (high - low + 1) as u64) as i64
+  }
+
+  pub fn coin(&mut self) -> bool {
+    self.next_u64() & 1 == 1
+  }
+
+  pub fn shuffle<T>(&mut self, items: &mut [T]) {
+    for i in (1 .. items.len()).rev() {
+      items.swap(i, self.below(i + 1));
+    }
+  }
+}
+
+/// Generated puzzle input, with the answers known from the way it was built. Serializes
+/// to the format of an example sidecar
+#[derive(Debug, Serialize)]
+pub struct Generated {
+  #[serde(skip)]
+  pub input: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub part1: Option<Answer>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub part2: Option<Answer>
+}
+
+impl Generated {
+  fn new(lines: &[String], part1: Option<Answer>, part2: Option<Answer>) -> Generated {
+    Generated { input: lines.join("\n") + "\n", part1, part2 }
+  }
+
+  pub fn has_answers(&self) -> bool {
+    self.part1.is_some() || self.part2.is_some()
+  }
+
+  pub fn sidecar(&self) -> Result<String> {
+    toml::to_string(self).map_err(|e| Error::InvalidInput(format!("Unable to serialize answers: {}", e)))
+  }
+}
+
+/// Answers of generated inputs are bounded by their size, one too large to store is left unknown
+fn known(value: usize) -> Option<Answer> {
+  Answer::try_from(value).ok()
+}
+
+/// Path of the sidecar with the known answers of an input generated into `path`
+pub fn sidecar_path(path: &str) -> String {
+  format!("{}{}", path.strip_suffix(".txt").unwrap_or(path), SIDECAR_SUFFIX)
+}
+
+/// Size used when none is given: the number of lines, claims, shifts, units, steps
+/// or nodes, depending on the day, or the last marble of day 9
+pub fn default_size(day: u8) -> usize {
+  match day {
+    4 => 100,
+    5 => 10000,
+    7 => 26,
+    8 => 2000,
+    9 => 10000,
+    _ => 1000
+  }
+}
+
+fn check_size(day: u8, size: usize, min: usize, max: usize) -> Result<()> {
+  if size < min || size > max {
+    Err(Error::InvalidInput(format!("Size of day {} input has to be between {} and {}, got {}", day, min, max, size)))
+  } else {
+    Ok(())
+  }
+}
+
+/// Generates a valid random input of the day, the same one for the same seed and size
+pub fn generate(day: u8, seed: u64, size: usize) -> Result<Generated> {
+  let mut rng = Rng::new(seed);
+
+  match day {
+    1 => check_size(day, size, 2, 100_000).map(|_| frequencies(&mut rng, size)),
+    2 => check_size(day, size, 2, 100_000).map(|_| box_ids(&mut rng, size)),
+    3 => check_size(day, size, 3, 100_000).map(|_| claims(&mut rng, size)),
+    4 => check_size(day, size, 1, 10_000).map(|_| guard_log(&mut rng, size)),
+    5 => check_size(day, size, 1, 10_000_000).map(|_| polymer(&mut rng, size)),
+    7 => check_size(day, size, 2, 26).map(|_| step_graph(&mut rng, size)),
+    8 => check_size(day, size, 1, 1_000_000).map(|_| license_tree(&mut rng, size)),
+    9 => check_size(day, size, 1, 10_000_000).map(|_| marble_game(&mut rng, size)),
+    _ => Err(Error::InvalidInput(format!(
+      "There is no generator for day {}, available: {}",
+      day,
+      GENERATED_DAYS.iter().map(|d| d.to_string()).collect::<Vec<String>>().join(", ")
+    )))
+  }
+}
+
+/// Picks distinct frequencies for every change but the last one, which goes back to one of
+/// them. That makes it the first frequency reached twice, as well as the final frequency
+fn frequencies(rng: &mut Rng, size: usize) -> Generated {
+  let bound = size as i64 * 10;
+  let mut seen: HashSet<i64> = HashSet::new();
+  let mut sums: Vec<i64> = vec![0];
+  seen.insert(0);
+
+  while sums.len() < size {
+    let sum = rng.between(-bound, bound);
+    if seen.insert(sum) {
+      sums.push(sum);
+    }
+  }
+  let repeated = sums[rng.below(size - 1)];
+  sums.push(repeated);
+
+  let lines: Vec<String> = sums.windows(2).map(|w| format!("{:+}", w[1] - w[0])).collect();
+  Generated::new(&lines, Some(repeated.into()), Some(repeated.into()))
+}
+
+const ID_LENGTH: usize = 26;
+
+fn random_id(rng: &mut Rng) -> Vec<u8> {
+  (0 .. ID_LENGTH).map(|_| b'a' + rng.below(26) as u8).collect()
+}
+
+/// Keys shared by two ids exactly when they differ in at most one position
+fn near_keys(id: &[u8]) -> impl Iterator<Item = Vec<u8>> + '_ {
+  (0 .. id.len()).map(move |i| {
+    let mut key = id.to_vec();
+    key[i] = b'_';
+    key
+  })
+}
+
+/// Plants a single pair of ids differing in one position, every other id differs
+/// from all the others in at least two
+fn box_ids(rng: &mut Rng, size: usize) -> Generated {
+  let planted = random_id(rng);
+  let position = rng.below(ID_LENGTH);
+  let mut twin = planted.clone();
+  twin[position] = b'a' + (((twin[position] - b'a') as usize + 1 + rng.below(25)) % 26) as u8;
+
+  let mut keys: HashSet<Vec<u8>> = near_keys(&planted).chain(near_keys(&twin)).collect();
+  let mut ids = vec![planted.clone(), twin];
+
+  while ids.len() < size {
+    let id = random_id(rng);
+    if near_keys(&id).all(|k| !keys.contains(&k)) {
+      keys.extend(near_keys(&id));
+      ids.push(id);
+    }
+  }
+  rng.shuffle(&mut ids);
+
+  let counts = |id: &Vec<u8>| {
+    let mut letters: HashMap<u8, usize> = HashMap::new();
+    id.iter().for_each(|l| *letters.entry(*l).or_insert(0) += 1);
+    (letters.values().any(|c| *c == 2), letters.values().any(|c| *c == 3))
+  };
+  let twos = ids.iter().filter(|id| counts(id).0).count();
+  let threes = ids.iter().filter(|id| counts(id).1).count();
+
+  let mut common = planted;
+  common.remove(position);
+
+  let lines: Vec<String> = ids.into_iter().map(|id| String::from_utf8(id).unwrap()).collect();
+  Generated::new(&lines, known(twos * threes), Some(String::from_utf8(common).unwrap().into()))
+}
+
+const FABRIC_SIZE: usize = 1000;
+/// Every claim but the planted one fits left of this column, the planted one right of it
+const PLANTED_COLUMN: usize = 900;
+const MAX_CLAIM_SIZE: usize = 30;
+
+/// Places a single claim in a strip of the fabric nobody else claims, every other claim
+/// starts inside of the claim before it or is overlapped by the one after it. The overlapping
+/// area isn't known upfront, so only the answer of part 2 is
+fn claims(rng: &mut Rng, size: usize) -> Generated {
+  let others = size - 1;
+  let mut rects: Vec<(usize, usize, usize, usize)> = Vec::with_capacity(size);
+
+  for i in 0 .. others {
+    let overlapping = i % 2 == 1 || (i > 0 && i == others - 1);
+    let (x, y) = match (overlapping, rects.last()) {
+      (true, Some(&(x, y, w, h))) => (x + rng.below(w), y + rng.below(h)),
+      _ => (rng.below(PLANTED_COLUMN - MAX_CLAIM_SIZE), rng.below(FABRIC_SIZE - MAX_CLAIM_SIZE))
+    };
+    let w = 1 + rng.below(MAX_CLAIM_SIZE.min(PLANTED_COLUMN - x));
+    let h = 1 + rng.below(MAX_CLAIM_SIZE.min(FABRIC_SIZE - y));
+    rects.push((x, y, w, h));
+  }
+
+  let planted = rng.below(size);
+  let w = 1 + rng.below(MAX_CLAIM_SIZE);
+  let h = 1 + rng.below(MAX_CLAIM_SIZE);
+  let x = PLANTED_COLUMN + rng.below(FABRIC_SIZE - PLANTED_COLUMN - w + 1);
+  let y = rng.below(FABRIC_SIZE - h + 1);
+  rects.insert(planted, (x, y, w, h));
+
+  let lines: Vec<String> = rects.iter().enumerate()
+    .map(|(i, (x, y, w, h))| format!("#{} @ {},{}: {}x{}", i + 1, x, y, w, h))
+    .collect();
+  Generated::new(&lines, None, known(planted + 1))
+}
+
+/// Index of the largest value, unless it is shared by several of them
+fn unique_max<K: Copy, I: Iterator<Item = (K, usize)>>(values: I) -> Option<K> {
+  let mut best: Option<(K, usize)> = None;
+  let mut tied = false;
+
+  for (key, value) in values {
+    match best {
+      Some((_, max)) if value < max => {},
+      Some((_, max)) if value == max => tied = true,
+      _ => {
+        best = Some((key, value));
+        tied = false;
+      }
+    }
+  }
+
+  best.filter(|_| !tied).map(|(key, _)| key)
+}
+
+/// Writes one shift per night, in random order. The first guard takes a third of the shifts
+/// and always naps through the same minute, so the answers are rarely tied. They are only
+/// given when the sleepiest guard, their sleepiest minute and the most slept minute are unique
+fn guard_log(rng: &mut Rng, size: usize) -> Generated {
+  let first_night = NaiveDate::from_ymd_opt(1518, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+  let mut ids: Vec<usize> = Vec::new();
+  while ids.len() < 1 + size / 8 {
+    let id = 1 + rng.below(4000);
+    if !ids.contains(&id) {
+      ids.push(id);
+    }
+  }
+  let favourite_minute = rng.between(10, 50);
+
+  let mut asleep: BTreeMap<usize, [usize; 60]> = BTreeMap::new();
+  let mut lines: Vec<String> = Vec::new();
+  let timestamp = |night: i64, minute: i64| (first_night + Duration::days(night) + Duration::minutes(minute)).format("[%Y-%m-%d %H:%M]");
+
+  for night in 0 .. size as i64 {
+    let sleepy = rng.below(3) == 0;
+    let id = if sleepy { ids[0] } else { ids[rng.below(ids.len())] };
+    let start = if rng.coin() { -rng.between(1, 15) } else { rng.between(0, 5) };
+    lines.push(format!("{} Guard #{} begins shift", timestamp(night, start), id));
+
+    let naps = rng.below(4).max(usize::from(sleepy));
+    let minutes = loop {
+      let mut minutes: Vec<i64> = Vec::new();
+      while minutes.len() < naps * 2 {
+        let minute = rng.between(start.max(0) + 1, 59);
+        if !minutes.contains(&minute) {
+          minutes.push(minute);
+        }
+      }
+      minutes.sort_unstable();
+
+      if !sleepy || minutes.chunks(2).any(|nap| nap[0] <= favourite_minute && favourite_minute < nap[1]) {
+        break minutes;
+      }
+    };
+
+    let tally = asleep.entry(id).or_insert([0; 60]);
+    for nap in minutes.chunks(2) {
+      lines.push(format!("{} falls asleep", timestamp(night, nap[0])));
+      lines.push(format!("{} wakes up", timestamp(night, nap[1])));
+      tally[nap[0] as usize .. nap[1] as usize].iter_mut().for_each(|m| *m += 1);
+    }
+  }
+  rng.shuffle(&mut lines);
+
+  let part1 = unique_max(asleep.iter().map(|(id, tally)| (*id, tally.iter().sum())))
+    .and_then(|id| unique_max(asleep[&id].iter().cloned().enumerate()).map(|minute| id * minute));
+  let part2 = unique_max(asleep.iter().flat_map(|(id, tally)| tally.iter().enumerate().map(move |(m, c)| ((*id, m), *c))))
+    .map(|(id, minute)| id * minute);
+
+  Generated::new(&lines, part1.and_then(known), part2.and_then(known))
+}
+
+fn flip_case(unit: u8) -> u8 {
+  unit ^ 0x20
+}
+
+fn random_unit(rng: &mut Rng) -> u8 {
+  let unit = b'a' + rng.below(26) as u8;
+  if rng.coin() { flip_case(unit) } else { unit }
+}
+
+/// Surrounds a polymer that can't react with random pairs of reacting units, nested
+/// like brackets. Reduction always ends with the same polymer whatever the order of
+/// reactions, so it gives back the core
+fn polymer(rng: &mut Rng, size: usize) -> Generated {
+  let mut core: Vec<u8> = Vec::new();
+  while core.len() < size - size / 4 * 2 {
+    let unit = random_unit(rng);
+    if core.last() != Some(&flip_case(unit)) {
+      core.push(unit);
+    }
+  }
+
+  let mut pairs = size / 4;
+  let mut open: Vec<u8> = Vec::new();
+  let mut polymer: Vec<u8> = Vec::with_capacity(size);
+  let mut core_units = core.iter().peekable();
+
+  while pairs > 0 || !open.is_empty() || core_units.peek().is_some() {
+    let choices = [pairs > 0, !open.is_empty(), open.is_empty() && core_units.peek().is_some()];
+    let available: Vec<usize> = (0 .. choices.len()).filter(|c| choices[*c]).collect();
+
+    match available[rng.below(available.len())] {
+      0 => {
+        let unit = random_unit(rng);
+        polymer.push(unit);
+        open.push(unit);
+        pairs -= 1;
+      },
+      1 => polymer.push(flip_case(open.pop().unwrap())),
+      _ => polymer.push(*core_units.next().unwrap())
+    }
+  }
+
+  Generated::new(&[String::from_utf8(polymer).unwrap()], known(core.len()), None)
+}
+
+/// Orders the steps randomly and makes each one depend on one to three of the steps
+/// before it, so the graph is acyclic and connected
+fn step_graph(rng: &mut Rng, size: usize) -> Generated {
+  let mut steps: Vec<char> = ('A' ..= 'Z').take(size).collect();
+  rng.shuffle(&mut steps);
+
+  let mut lines: Vec<String> = Vec::new();
+  for i in 1 .. size {
+    let mut parents: Vec<usize> = Vec::new();
+    let count = 1 + rng.below(i.min(3));
+    while parents.len() < count {
+      let parent = rng.below(i);
+      if !parents.contains(&parent) {
+        parents.push(parent);
+      }
+    }
+
+    for parent in parents {
+      lines.push(format!("Step {} must be finished before step {} can begin.", steps[parent], steps[i]));
+    }
+  }
+  rng.shuffle(&mut lines);
+
+  Generated::new(&lines, None, None)
+}
+
+/// Writes a node with exactly `nodes` nodes in its subtree, returns its metadata sum and value
+fn license_node(rng: &mut Rng, nodes: usize, tree: &mut Vec<usize>) -> (usize, usize) {
+  let children = if nodes > 1 { 1 + rng.below((nodes - 1).min(4)) } else { 0 };
+  // Entries of nodes with children are indices, mostly pointing at one of the children
+  let largest = if children > 0 { children + 1 } else { 9 };
+  let metadata: Vec<usize> = (0 .. 1 + rng.below(3)).map(|_| 1 + rng.below(largest)).collect();
+
+  // Splits the descendants between the children, every one of them gets at least one node
+  let mut cuts: Vec<usize> = vec![0, nodes - 1];
+  while cuts.len() < children + 1 {
+    let cut = 1 + rng.below(nodes - 2);
+    if !cuts.contains(&cut) {
+      cuts.push(cut);
+    }
+  }
+  cuts.sort_unstable();
+  if children == 0 {
+    cuts.clear();
+  }
+
+  tree.push(children);
+  tree.push(metadata.len());
+  let (mut sum, mut values) = (0, Vec::new());
+  for part in cuts.windows(2) {
+    let (child_sum, child_value) = license_node(rng, part[1] - part[0], tree);
+    sum += child_sum;
+    values.push(child_value);
+  }
+  tree.extend_from_slice(&metadata);
+
+  let value = if children == 0 {
+    metadata.iter().sum()
+  } else {
+    metadata.iter().filter_map(|m| values.get(m - 1)).sum()
+  };
+
+  (sum + metadata.iter().sum::<usize>(), value)
+}
+
+/// Builds a random tree of `size` nodes, its metadata sum and root value are
+/// computed along the way
+fn license_tree(rng: &mut Rng, size: usize) -> Generated {
+  let mut tree: Vec<usize> = Vec::new();
+  let (sum, value) = license_node(rng, size, &mut tree);
+
+  let line = tree.iter().map(|n| n.to_string()).collect::<Vec<String>>().join(" ");
+  Generated::new(&[line], known(sum), known(value))
+}
+
+fn marble_game(rng: &mut Rng, size: usize) -> Generated {
+  let players = 2 + rng.below(499);
+
+  Generated::new(&[format!("{} players; last marble is worth {} points", players, size)], None, None)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn same_seed_gives_same_input() {
+    for day in GENERATED_DAYS.iter() {
+      let first = generate(*day, 7, 20).unwrap();
+      let second = generate(*day, 7, 20).unwrap();
+
+      assert_eq!(first.input, second.input);
+      assert_eq!((first.part1, first.part2), (second.part1, second.part2));
+    }
+    assert_ne!(generate(5, 1, 100).unwrap().input, generate(5, 2, 100).unwrap().input);
+  }
+
+  #[test]
+  fn size_is_validated() {
+    assert!(generate(7, 0, 27).is_err());
+    assert!(generate(1, 0, 1).is_err());
+    assert!(generate(6, 0, 10).is_err());
+  }
+
+  #[test]
+  fn answers_are_written_to_sidecar() {
+    let generated = generate(8, 3, 10).unwrap();
+
+    assert_eq!(sidecar_path("data/day8/generated.txt"), "data/day8/generated.expected.toml");
+    assert!(generated.sidecar().unwrap().starts_with("part1 = "));
+  }
+}
@@ -0,0 +1,226 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, ExitStatus, Stdio};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::registry;
+use crate::runner::{self, Job, PartResult, Status};
+use crate::solver::{Context, Params};
+
+/// Limits used when none are given, generous enough for every day solved the usual way
+pub const DEFAULT_CPU_SECONDS: u64 = 60;
+pub const DEFAULT_MEMORY_MIB: u64 = 4096;
+
+/// Resources a child process solving a part may use
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Limits {
+  /// CPU time, the child is killed once it runs out
+  pub cpu_seconds: u64,
+  /// Address space, allocations over it fail and abort the child
+  pub memory_mib: u64
+}
+
+impl Default for Limits {
+  fn default() -> Limits {
+    Limits { cpu_seconds: DEFAULT_CPU_SECONDS, memory_mib: DEFAULT_MEMORY_MIB }
+  }
+}
+
+/// Part the parent asks the child to solve, written to the standard input of the child
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChildJob {
+  pub day: u8,
+  pub part: u8,
+  pub input: String,
+  pub params: Params,
+  pub strategy: Option<String>,
+  pub timeout_ms: Option<u64>,
+  pub limits: Limits
+}
+
+/// Outcome of the part, written by the child to its standard output
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChildReport {
+  pub status: Status,
+  pub elapsed: Duration,
+  pub input_hash: Option<String>
+}
+
+impl ChildJob {
+  fn new(job: &Job, limits: Limits) -> ChildJob {
+    ChildJob {
+      day: job.day.day,
+      part: job.part,
+      input: job.input.clone(),
+      params: job.ctx.params.clone(),
+      strategy: job.ctx.strategy.clone(),
+      timeout_ms: job.ctx.budget.map(|budget| budget.as_millis() as u64),
+      limits
+    }
+  }
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+type Resource = libc::__rlimit_resource_t;
+#[cfg(all(unix, not(all(target_os = "linux", target_env = "gnu"))))]
+type Resource = libc::c_int;
+
+#[cfg(unix)]
+fn set_limit(resource: Resource, soft: u64, hard: u64) -> Result<()> {
+  let limit = libc::rlimit { rlim_cur: soft as libc::rlim_t, rlim_max: hard as libc::rlim_t };
+
+  // Safe, the limit is a valid rlimit that outlives the call
+  match unsafe { libc::setrlimit(resource, &limit) } {
+    0 => Ok(()),
+    _ => Err(Error::InvalidInput(format!("Unable to set resource limit: {}", std::io::Error::last_os_error())))
+  }
+}
+
+/// Limits the resources of the current process. The CPU limit sends SIGXCPU first and
+/// kills the process a second later
+#[cfg(unix)]
+pub fn apply(limits: &Limits) -> Result<()> {
+  set_limit(libc::RLIMIT_CPU, limits.cpu_seconds, limits.cpu_seconds.saturating_add(1))?;
+
+  let memory = limits.memory_mib.saturating_mul(1024 * 1024);
+  set_limit(libc::RLIMIT_AS, memory, memory)
+}
+
+#[cfg(not(unix))]
+pub fn apply(_limits: &Limits) -> Result<()> {
+  Err(Error::InvalidInput("Resource limits are only supported on Unix".to_string()))
+}
+
+/// Solves the job without any limits, the part of the child that doesn't affect the process
+pub fn solve(job: &ChildJob) -> Result<ChildReport> {
+  let day = registry::find(job.day)
+    .ok_or_else(|| Error::InvalidInput(format!("Day {} is not linked into the binary", job.day)))?;
+  let ctx = Context::with_params(job.params.clone())
+    .with_strategy(job.strategy.clone())
+    .with_budget(job.timeout_ms.map(Duration::from_millis));
+
+  let result = runner::run_day(&day, &job.input, &[job.part], &ctx).pop()
+    .ok_or_else(|| Error::InvalidInput(format!("Day {} has no part {}", job.day, job.part)))?;
+
+  Ok(ChildReport { status: result.status, elapsed: result.elapsed, input_hash: result.input_hash })
+}
+
+/// Child side of an isolated part, reads the job, limits the process and solves the part.
+/// Returns the report to be written back to the parent
+pub fn serve(job: &str) -> Result<String> {
+  let job: ChildJob = serde_json::from_str(job)
+    .map_err(|e| Error::InvalidInput(format!("Unable to read isolated job: {}", e)))?;
+
+  apply(&job.limits)?;
+  let report = solve(&job)?;
+
+  serde_json::to_string(&report)
+    .map_err(|e| Error::InvalidInput(format!("Unable to write isolated report: {}", e)))
+}
+
+#[cfg(unix)]
+fn describe_exit(status: ExitStatus) -> String {
+  use std::os::unix::process::ExitStatusExt;
+
+  match status.signal() {
+    Some(libc::SIGXCPU) => "CPU time limit exceeded".to_string(),
+    Some(libc::SIGKILL) => "Killed".to_string(),
+    Some(libc::SIGSEGV) => "Crashed with a segmentation fault".to_string(),
+    Some(libc::SIGABRT) => "Aborted".to_string(),
+    Some(signal) => format!("Killed by signal {}", signal),
+    None => format!("Exited with {}", status)
+  }
+}
+
+#[cfg(not(unix))]
+fn describe_exit(status: ExitStatus) -> String {
+  format!("Exited with {}", status)
+}
+
+/// Runs the job in a child process of `program`, failures of the child, like being killed
+/// or running out of memory, are reported as a failure of the part
+fn run_child(program: &Path, job: &Job, limits: Limits) -> PartResult {
+  let result = |status: Status, elapsed: Duration, input_hash: Option<String>| PartResult {
+    day: job.day.day, part: job.part, input: job.input.clone(), input_hash, elapsed, status, cached: false, memory: None
+  };
+  let failed = |message: String| result(Status::Failed(message), Duration::default(), None);
+
+  let child_job = match serde_json::to_string(&ChildJob::new(job, limits)) {
+    Ok(child_job) => child_job,
+    Err(e) => return failed(format!("Unable to write isolated job: {}", e))
+  };
+  // Without backtraces the last line of the standard error describes what went wrong
+  let child = Command::new(program)
+    .arg("isolated")
+    .env("RUST_BACKTRACE", "0")
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn();
+  let mut child = match child {
+    Ok(child) => child,
+    Err(e) => return failed(format!("Unable to start {}: {}", program.display(), e))
+  };
+
+  // A child dying before reading the job shows up in its exit status, not here
+  if let Some(mut stdin) = child.stdin.take() {
+    let _ = stdin.write_all(child_job.as_bytes());
+  }
+
+  let output = match child.wait_with_output() {
+    Ok(output) => output,
+    Err(e) => return failed(format!("Unable to wait for the isolated part: {}", e))
+  };
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  let last_line = stderr.lines().rev().find(|line| !line.trim().is_empty() && !line.starts_with("note: "));
+
+  match serde_json::from_slice::<ChildReport>(&output.stdout) {
+    Ok(report) if output.status.success() => result(report.status, report.elapsed, report.input_hash),
+    _ if output.status.success() => failed("Isolated part didn't report its result".to_string()),
+    _ => failed(match (output.status.code(), last_line) {
+      // Errors of the child itself, like an unknown day, are already described
+      (Some(_), Some(line)) => line.to_string(),
+      (_, Some(line)) => format!("{}: {}", describe_exit(output.status), line),
+      (_, None) => describe_exit(output.status)
+    })
+  }
+}
+
+/// Runs every job in its own child process of `program`, which has to be this binary, on
+/// a pool of `workers` threads. Results are returned in the order of the jobs
+pub fn run_isolated(jobs: &[Job], workers: usize, program: &Path, limits: Limits) -> Vec<PartResult> {
+  runner::run_pool(jobs, workers, |job| Some(run_child(program, job, limits)))
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[cfg(unix)]
+  #[test]
+  fn exits_by_signal_are_described() {
+    use std::os::unix::process::ExitStatusExt;
+
+    assert_eq!(describe_exit(ExitStatus::from_raw(libc::SIGXCPU)), "CPU time limit exceeded");
+    assert_eq!(describe_exit(ExitStatus::from_raw(libc::SIGKILL)), "Killed");
+    assert_eq!(describe_exit(ExitStatus::from_raw(libc::SIGTERM)), format!("Killed by signal {}", libc::SIGTERM));
+  }
+
+  #[cfg(feature = "day7")]
+  #[test]
+  fn child_jobs_are_solved_and_reported() {
+    let job = ChildJob {
+      day: 7, part: 2, input: "data/day7/input_example.txt".to_string(), params: serde_json::from_str("{\"workers\": 2, \"offset\": 0}").unwrap(),
+      strategy: None, timeout_ms: None, limits: Limits::default()
+    };
+    let job: ChildJob = serde_json::from_str(&serde_json::to_string(&job).unwrap()).unwrap();
+
+    let report = solve(&job).expect("Unable to solve job");
+    let report: ChildReport = serde_json::from_str(&serde_json::to_string(&report).unwrap()).unwrap();
+
+    assert_eq!(report.status, Status::Solved(15.into()));
+    assert!(report.input_hash.is_some());
+  }
+}
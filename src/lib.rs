@@ -0,0 +1,42 @@
+#[macro_use] extern crate lazy_static;
+extern crate regex;
+extern crate chrono;
+extern crate time;
+extern crate serde;
+extern crate toml;
+extern crate serde_json;
+extern crate memmap2;
+extern crate ureq;
+#[cfg(unix)] extern crate libc;
+
+#[cfg(feature = "day1")] pub mod day1;
+#[cfg(feature = "day2")] pub mod day2;
+#[cfg(feature = "day3")] pub mod day3;
+#[cfg(feature = "day4")] pub mod day4;
+#[cfg(feature = "day5")] pub mod day5;
+#[cfg(feature = "day7")] pub mod day7;
+#[cfg(feature = "day8")] pub mod day8;
+#[cfg(feature = "day9")] pub mod day9;
+
+pub mod bench;
+pub mod cancel;
+pub mod cache;
+pub mod compare;
+pub mod config;
+pub mod error;
+pub mod examples;
+pub mod fetch;
+pub mod fuzz;
+pub mod generator;
+pub mod isolate;
+pub mod memory;
+pub mod registry;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod solver;
+pub mod submit;
+pub mod trace;
+pub mod utils;
+pub mod verify;
+pub mod watch;
@@ -1,67 +1,500 @@
-#[macro_use] extern crate lazy_static;
-extern crate regex;
-extern crate chrono;
-extern crate time;
-
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day7;
-mod day8;
-mod day9;
-
-mod utils;
+extern crate rust_advent_of_code_2018;
 
-fn main() {
-    if cfg!(feature = "day1") {
-        println!("Day1, part1: {}", day1::solve_part1("data/day1/input.txt"));
-        println!("Day1, part2: {:?}", day1::solve_part2("data/day1/input.txt"));
+mod cli;
+
+use std::io::Read;
+use std::path::Path;
+use std::process;
+use std::time::Duration;
+
+use crate::cli::{Command, CliError, DaySelection, Overrides, RunOptions};
+use rust_advent_of_code_2018::bench::{self, Baseline};
+use rust_advent_of_code_2018::cache::{self, AnswerCache};
+use rust_advent_of_code_2018::compare;
+use rust_advent_of_code_2018::config::{self, Config};
+use rust_advent_of_code_2018::error::Error;
+use rust_advent_of_code_2018::fetch::{self, Fetched};
+use rust_advent_of_code_2018::generator;
+use rust_advent_of_code_2018::isolate::{self, Limits};
+use rust_advent_of_code_2018::memory;
+use rust_advent_of_code_2018::registry::{self, Day};
+use rust_advent_of_code_2018::report::{self, Format};
+use rust_advent_of_code_2018::runner::{self, Job, PartResult, Status};
+use rust_advent_of_code_2018::scaffold;
+use rust_advent_of_code_2018::solver::Answer;
+use rust_advent_of_code_2018::submit::{self, Submission, Verdict};
+use rust_advent_of_code_2018::trace::{self, Trace};
+use rust_advent_of_code_2018::verify::{self, Answers};
+use rust_advent_of_code_2018::watch;
+
+fn select_days(days: DaySelection) -> Result<Vec<Day>, CliError> {
+    match days {
+        DaySelection::All => Ok(registry::days()),
+        DaySelection::Day(day) => Ok(vec![registry::find(day).ok_or_else(||
+            CliError::UnknownDay(day, registry::days().iter().map(|d| d.day).collect())
+        )?])
+    }
+}
+
+fn select_parts(part: Option<u8>) -> Vec<u8> {
+    part.map_or_else(|| vec![1, 2], |p| vec![p])
+}
+
+/// Reads the project configuration and applies the command line options on top of it.
+/// A missing default config is fine, one given explicitly has to exist
+fn load_config(overrides: &Overrides) -> Result<Config, Error> {
+    let mut config = match &overrides.config {
+        Some(path) => Config::load(path)?,
+        None => Config::load_or_default(config::DEFAULT_CONFIG_PATH)?
+    };
+
+    if let Some(data_dir) = &overrides.data_dir {
+        config.data_dir = data_dir.clone();
+    }
+    if overrides.timeout_ms.is_some() {
+        config.timeout_ms = overrides.timeout_ms;
+    }
+    for day in registry::days() {
+        let day_config = config.day_mut(day.day);
+        for (name, value) in &overrides.params {
+            day_config.params.set(name, *value);
+        }
+        if overrides.strategy.is_some() {
+            day_config.strategy = overrides.strategy.clone();
+        }
+        if overrides.timeout_ms.is_some() {
+            day_config.timeout_ms = overrides.timeout_ms;
+        }
+    }
+
+    Ok(config)
+}
+
+fn print_results(results: &[PartResult], format: Format) {
+    match format {
+        Format::Text => for result in results {
+            match &result.status {
+                Status::Solved(answer) if result.cached => println!("Day{}, part{}: {} (cached)", result.day, result.part, answer),
+                Status::Solved(answer) => match &result.memory {
+                    Some(stats) => println!("Day{}, part{}: {} ({})", result.day, result.part, answer, stats),
+                    None => println!("Day{}, part{}: {}", result.day, result.part, answer)
+                },
+                Status::Failed(e) => eprintln!("Day{}, part{}: {}", result.day, result.part, e),
+                Status::TimedOut(budget) => eprintln!("Day{}, part{}: timed out after {:?}", result.day, result.part, budget)
+            }
+        },
+        Format::Json => match report::render_json(results) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("{}", e)
+        },
+        Format::Markdown => print!("{}", report::render_markdown(results))
+    }
+}
+
+/// Prints what the solvers explained, in the same order as the results
+fn print_traces(jobs: &[Job], format: Format) {
+    for job in jobs {
+        let events = job.ctx.trace.events();
+        match format {
+            Format::Json => match trace::render_json_lines(job.day.day, job.part, &events) {
+                Ok(lines) => eprint!("{}", lines),
+                Err(e) => eprintln!("{}", e)
+            },
+            _ => eprint!("Day{}, part{}:\n{}", job.day.day, job.part, trace::render_text(&events, 1))
+        }
     }
+}
 
-    if cfg!(feature = "day2") {
-        println!("Day2, part1: {}", day2::solve_part1("data/day2/input.txt"));
-        println!("Day2, part2: {:?}", day2::solve_part2("data/day2/input.txt"));
+/// Runs the selected days, returns false if any of them failed
+fn run(
+    days: DaySelection, part: Option<u8>, input: Option<String>, options: RunOptions, overrides: Overrides
+) -> Result<bool, CliError> {
+    let RunOptions { format, jobs: workers, no_cache, explain, memory: measure_memory, isolate, cpu_limit, memory_limit } = options;
+    let selected = select_days(days)?;
+    let parts = select_parts(part);
+    let config = match load_config(&overrides) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            return Ok(false);
+        }
+    };
+    if let Err(e) = memory::track(measure_memory) {
+        eprintln!("{}", e);
+        return Ok(false);
     }
+    let jobs: Vec<Job> = selected.into_iter()
+        .flat_map(|day| {
+            let path = input.clone().unwrap_or_else(|| config.input(day.day));
+            let ctx = config.context(day.day);
+            parts.iter().map(move |part| {
+                // Every part gets its own trace, so the events of parts solved in parallel don't mix
+                let trace = if explain { Trace::enabled() } else { Trace::default() };
+                Job { day, part: *part, input: path.clone(), ctx: ctx.clone().with_trace(trace) }
+            })
+        })
+        .collect();
 
-    if cfg!(feature = "day3") {
-        println!("Day3, part1: {}", day3::solve_part1("data/day3/input.txt"));
-        println!("Day3, part1: {}", day3::solve_part2("data/day3/input.txt"));
+    // A broken cache is only a missed shortcut, the answers are solved again and the cache rewritten.
+    // Cached answers have nothing to explain or measure, so those solve everything again
+    let mut answer_cache = if no_cache || explain || measure_memory {
+        None
+    } else {
+        Some(AnswerCache::load(cache::DEFAULT_CACHE_PATH).unwrap_or_else(|e| {
+            eprintln!("Ignoring answer cache: {}", e);
+            AnswerCache::default()
+        }))
+    };
+    let workers = workers.unwrap_or_else(runner::default_workers);
+    let results = if isolate {
+        let limits = Limits {
+            cpu_seconds: cpu_limit.unwrap_or(isolate::DEFAULT_CPU_SECONDS),
+            memory_mib: memory_limit.unwrap_or(isolate::DEFAULT_MEMORY_MIB)
+        };
+        match std::env::current_exe() {
+            Ok(program) => isolate::run_isolated(&jobs, workers, &program, limits),
+            Err(e) => {
+                eprintln!("Unable to find the binary to isolate parts in: {}", e);
+                return Ok(false);
+            }
+        }
+    } else {
+        runner::run_parallel(&jobs, workers, answer_cache.as_ref())
+    };
+    print_results(&results, format);
+    if explain {
+        print_traces(&jobs, format);
     }
 
-    if cfg!(feature = "day4") {
-        println!("Day4, part1: {}", day4::solve_part1("data/day4/input.txt"));
-        println!("Day4, part2: {}", day4::solve_part2("data/day4/input.txt"));
+    if let Some(answer_cache) = answer_cache.as_mut() {
+        let before = answer_cache.len();
+        for (job, result) in jobs.iter().zip(&results) {
+            if let (Some(key), Status::Solved(answer)) = (runner::cache_key(result, &job.day, &job.ctx), &result.status) {
+                answer_cache.insert(key, answer.clone());
+            }
+        }
+        if answer_cache.len() != before {
+            if let Err(e) = answer_cache.save(cache::DEFAULT_CACHE_PATH) {
+                eprintln!("Unable to save answer cache: {}", e);
+            }
+        }
     }
 
-    if cfg!(feature = "day5") {
-        println!("Day5, part1: {}", day5::solve_part1("data/day5/input.txt"));
-        println!("Day5, part2: {}", day5::solve_part2("data/day5/input.txt"));
+    Ok(!results.iter().any(|r| r.is_failure()))
+}
+
+/// Checks solvers against confirmed answers, returns false if any of them doesn't match
+fn verify(answers: Option<String>) -> Result<bool, CliError> {
+    let path = answers.unwrap_or_else(|| verify::DEFAULT_ANSWERS_PATH.to_string());
+
+    match Answers::load(&path) {
+        Ok(answers) => {
+            let checks = verify::verify(&answers);
+            print!("{}", verify::render_table(&checks));
+            Ok(!checks.iter().any(|c| c.is_failure()))
+        },
+        Err(e) => {
+            eprintln!("{}", e);
+            Ok(false)
+        }
     }
+}
 
-    if cfg!(feature = "day7") {
-        println!("Day7, part1: {}", day7::solve_part1("data/day7/input.txt"));
-        println!("Day7, part2: {}", day7::solve_part2("data/day7/input.txt"));
+/// Benchmarks the selected days, returns false if any of them failed or regressed
+fn bench(
+    days: DaySelection, part: Option<u8>, iterations: Option<usize>,
+    save: Option<String>, baseline: Option<String>, threshold: Option<f64>, overrides: Overrides
+) -> Result<bool, CliError> {
+    let selected = select_days(days)?;
+    let parts = select_parts(part);
+    let config = match load_config(&overrides) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            return Ok(false);
+        }
+    };
+    let iterations = iterations.unwrap_or(bench::DEFAULT_ITERATIONS);
+    let threshold = threshold.unwrap_or(bench::DEFAULT_THRESHOLD);
+    let baseline = match baseline.map(|path| Baseline::load(&path)).transpose() {
+        Ok(baseline) => baseline,
+        Err(e) => {
+            eprintln!("{}", e);
+            return Ok(false);
+        }
+    };
+    let mut measurements = Vec::new();
+    let mut succeeded = true;
+
+    for day in selected {
+        match bench::bench_day(
+            &day, &config.input(day.day), &parts, iterations, &config.context(day.day)
+        ) {
+            Ok(mut m) => measurements.append(&mut m),
+            Err(e) => {
+                eprintln!("Day{}: {}", day.day, e);
+                succeeded = false;
+            }
+        }
     }
 
-    if cfg!(feature = "day8") {
-        println!("Day8, example part1: {}", day8::solve_part1("data/day8/example.txt"));
-        println!("Day8, part1: {}", day8::solve_part1("data/day8/input.txt"));
+    print!("{}", bench::render_table(&measurements, baseline.as_ref(), threshold));
 
-        println!("Day8, example part2: {}", day8::solve_part2("data/day8/example.txt"));
-        println!("Day8, part2: {}", day8::solve_part2("data/day8/input.txt"));
+    if let Some(baseline) = baseline {
+        succeeded &= bench::regressions(&measurements, &baseline, threshold).is_empty();
+    }
+    if let Some(path) = save {
+        if let Err(e) = Baseline::save(&measurements, &path) {
+            eprintln!("{}", e);
+            succeeded = false;
+        }
     }
 
-    if cfg!(feature = "day9") {
-        println!("Day9, part1: {}", day9::solve_part1(410, 72059));
-        println!("Day9, part2: {}", day9::solve_part2(410, 7_205_900));
-        /*
-        println!("Day9, example part1: {}", day9::solve_part1("data/day9/example.txt"));
-        println!("Day9, part1: {}", day9::solve_part1("data/day9/input.txt"));
+    Ok(succeeded)
+}
+
+/// Downloads inputs of the selected days, returns false if any of them couldn't be fetched
+fn fetch(days: DaySelection, overrides: Overrides) -> Result<bool, CliError> {
+    let selected = select_days(days)?;
+    let config = match load_config(&overrides) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            return Ok(false);
+        }
+    };
+    let session = fetch::session_token(&config);
+    let mut succeeded = true;
+
+    for day in selected {
+        match fetch::fetch(day.day, &config, session.as_deref()) {
+            Ok(Fetched::Cached(path)) => println!("Day{}: cached {}", day.day, path),
+            Ok(Fetched::Downloaded(path, size)) => println!("Day{}: downloaded {} ({} bytes)", day.day, path, size),
+            Err(e) => {
+                eprintln!("Day{}: {}", day.day, e);
+                succeeded = false;
+            }
+        }
+    }
+
+    Ok(succeeded)
+}
+
+/// Submits the answer, solving the part first when no answer is given. Returns false unless
+/// the server accepted the answer
+fn submit(
+    day: u8, part: u8, answer: Option<Answer>, input: Option<String>, ledger: Option<String>, overrides: Overrides
+) -> Result<bool, CliError> {
+    let day = select_days(DaySelection::Day(day))?[0];
+    let ledger = ledger.unwrap_or_else(|| submit::DEFAULT_LEDGER_PATH.to_string());
+    let config = match load_config(&overrides) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            return Ok(false);
+        }
+    };
+    let answer = match answer {
+        Some(answer) => answer,
+        None => {
+            let path = input.unwrap_or_else(|| config.input(day.day));
+            let ctx = config.context(day.day);
+            match runner::run_day(&day, &path, &[part], &ctx).pop().map(|r| r.status) {
+                Some(Status::Solved(answer)) => answer,
+                Some(Status::Failed(e)) => {
+                    eprintln!("Day{}, part{}: {}", day.day, part, e);
+                    return Ok(false);
+                },
+                Some(Status::TimedOut(budget)) => {
+                    eprintln!("Day{}, part{}: timed out after {:?}", day.day, part, budget);
+                    return Ok(false);
+                },
+                None => return Ok(false)
+            }
+        }
+    };
+
+    match submit::submit(day.day, part, &answer, &config, fetch::session_token(&config).as_deref(), &ledger) {
+        Ok(Submission::Refused(reason)) => {
+            eprintln!("Not submitting {}: {}", answer, reason);
+            Ok(false)
+        },
+        Ok(Submission::Answered { verdict, message }) => {
+            println!("Day{}, part{}: {} ({:?})\n{}", day.day, part, answer, verdict, message);
+            Ok(verdict == Verdict::Correct)
+        },
+        Err(e) => {
+            eprintln!("{}", e);
+            Ok(false)
+        }
+    }
+}
+
+/// Generates and registers a new day in the current directory, returns false if it couldn't be done
+fn new_day(day: u8) -> Result<bool, CliError> {
+    match scaffold::new_day(Path::new("."), day) {
+        Ok(files) => {
+            for file in files {
+                println!("{}", file);
+            }
+            Ok(true)
+        },
+        Err(e) => {
+            eprintln!("{}", e);
+            Ok(false)
+        }
+    }
+}
+
+/// Watches the day until the process is stopped, returns false only if it couldn't be started
+fn watch(day: u8, interval: Option<u64>, overrides: Overrides) -> Result<bool, CliError> {
+    let day = select_days(DaySelection::Day(day))?[0];
+    let interval = interval.map_or(watch::DEFAULT_INTERVAL, Duration::from_millis);
+
+    match load_config(&overrides) {
+        Ok(config) => {
+            watch::watch(&day, &config, interval, None);
+            Ok(true)
+        },
+        Err(e) => {
+            eprintln!("{}", e);
+            Ok(false)
+        }
+    }
+}
+
+/// Removes the answer cache
+fn cache_clean() -> Result<bool, CliError> {
+    match AnswerCache::clean(cache::DEFAULT_CACHE_PATH) {
+        Ok(true) => println!("Removed {}", cache::DEFAULT_CACHE_PATH),
+        Ok(false) => println!("No answer cache at {}", cache::DEFAULT_CACHE_PATH),
+        Err(e) => {
+            eprintln!("{}", e);
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Solves the selected parts with every strategy, returns false if any of them failed or disagreed
+fn compare(days: DaySelection, part: Option<u8>, input: Option<String>, overrides: Overrides) -> Result<bool, CliError> {
+    let selected = select_days(days)?;
+    let parts = select_parts(part);
+    let config = match load_config(&overrides) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            return Ok(false);
+        }
+    };
+    let mut timings = Vec::new();
+    let mut succeeded = true;
+
+    for day in selected {
+        let path = input.clone().unwrap_or_else(|| config.input(day.day));
+        match compare::compare_day(&day, &path, &parts, &config.context(day.day)) {
+            Ok(mut t) => timings.append(&mut t),
+            Err(e) => {
+                eprintln!("Day{}: {}", day.day, e);
+                succeeded = false;
+            }
+        }
+    }
+
+    print!("{}", compare::render_table(&timings));
+    Ok(succeeded && compare::disagreements(&timings).is_empty())
+}
+
+/// Generates an input of the day, returns false if it couldn't be generated or written
+fn gen(day: u8, seed: Option<u64>, size: Option<usize>, output: Option<String>) -> Result<bool, CliError> {
+    let generated = match generator::generate(day, seed.unwrap_or(0), size.unwrap_or_else(|| generator::default_size(day))) {
+        Ok(generated) => generated,
+        Err(e) => {
+            eprintln!("{}", e);
+            return Ok(false);
+        }
+    };
+
+    let path = match output {
+        Some(path) => path,
+        None => {
+            print!("{}", generated.input);
+            for (part, answer) in [(1, &generated.part1), (2, &generated.part2)] {
+                if let Some(answer) = answer {
+                    eprintln!("Day{}, part{}: {}", day, part, answer);
+                }
+            }
+            return Ok(true);
+        }
+    };
+
+    let mut files = vec![(path.clone(), Ok(generated.input.clone()))];
+    if generated.has_answers() {
+        files.push((generator::sidecar_path(&path), generated.sidecar()));
+    }
+
+    for (file, content) in files {
+        match content.and_then(|c| std::fs::write(&file, c).map_err(|source| Error::Io { path: file.clone(), source })) {
+            Ok(()) => println!("{}", file),
+            Err(e) => {
+                eprintln!("{}", e);
+                return Ok(false);
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+/// Solves the part the parent process of `run --isolate` writes to the standard input,
+/// and writes back the report
+fn isolated() -> Result<bool, CliError> {
+    let mut job = String::new();
+    if let Err(e) = std::io::stdin().read_to_string(&mut job) {
+        eprintln!("Unable to read isolated job: {}", e);
+        return Ok(false);
+    }
+
+    match isolate::serve(&job) {
+        Ok(report) => {
+            println!("{}", report);
+            Ok(true)
+        },
+        Err(e) => {
+            eprintln!("{}", e);
+            Ok(false)
+        }
+    }
+}
+
+fn main() {
+    let result = cli::parse_args(std::env::args().skip(1)).and_then(|command| match command {
+        Command::Run { days, part, input, options, overrides } => run(days, part, input, options, overrides),
+        Command::Isolated => isolated(),
+        Command::Verify { answers } => verify(answers),
+        Command::Fetch { days, overrides } => fetch(days, overrides),
+        Command::Submit { day, part, answer, input, ledger, overrides } =>
+            submit(day, part, answer, input, ledger, overrides),
+        Command::NewDay { day } => new_day(day),
+        Command::Watch { day, interval, overrides } => watch(day, interval, overrides),
+        Command::CacheClean => cache_clean(),
+        Command::Compare { days, part, input, overrides } => compare(days, part, input, overrides),
+        Command::Gen { day, seed, size, output } => gen(day, seed, size, output),
+        Command::Bench { days, part, iterations, save, baseline, threshold, overrides } =>
+            bench(days, part, iterations, save, baseline, threshold, overrides),
+        Command::Help(command) => {
+            println!("{}", command.and_then(|c| cli::command_help(&c)).unwrap_or_else(|| cli::USAGE.to_string()));
+            Ok(true)
+        }
+    });
 
-        println!("Day9, example part2: {}", day9::solve_part2("data/day9/example.txt"));
-        println!("Day9, part2: {}", day9::solve_part2("data/day9/input.txt"));
-        */
+    match result {
+        Ok(true) => {},
+        Ok(false) => process::exit(1),
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            process::exit(2);
+        }
     }
 }
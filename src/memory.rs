@@ -0,0 +1,184 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use serde::Serialize;
+
+use crate::error::{Error, Result};
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static TRACKING: AtomicBool = AtomicBool::new(false);
+
+/// Allocator counting the allocations and bytes of the whole process on top of the system
+/// one. Installed as the global allocator by the `memory` feature
+pub struct CountingAllocator;
+
+#[cfg(feature = "memory")]
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocated(size: usize) {
+  ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+  let live = LIVE.fetch_add(size, Ordering::Relaxed) + size;
+  PEAK.fetch_max(live, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    let ptr = System.alloc(layout);
+    if !ptr.is_null() {
+      allocated(layout.size());
+    }
+    ptr
+  }
+
+  unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+    let ptr = System.alloc_zeroed(layout);
+    if !ptr.is_null() {
+      allocated(layout.size());
+    }
+    ptr
+  }
+
+  unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+    System.dealloc(ptr, layout);
+    LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+  }
+
+  /// Growing or shrinking counts as an allocation of the new size replacing the old one
+  unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+    let new_ptr = System.realloc(ptr, layout, new_size);
+    if !new_ptr.is_null() {
+      LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+      allocated(new_size);
+    }
+    new_ptr
+  }
+}
+
+/// Memory a part used while it was solved
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct MemoryStats {
+  /// Most bytes allocated at once on top of those live before the part started
+  pub peak_bytes: usize,
+  pub allocations: u64,
+  /// Bytes the part allocated and never freed, leaks show up here
+  pub live_bytes: usize
+}
+
+impl fmt::Display for MemoryStats {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f, "peak {}, {} allocations, {} live",
+      format_bytes(self.peak_bytes), self.allocations, format_bytes(self.live_bytes)
+    )
+  }
+}
+
+/// Counters at the start of a measurement
+pub struct Snapshot {
+  allocations: u64,
+  live: usize
+}
+
+/// Whether the counting allocator was compiled in
+pub fn is_installed() -> bool {
+  cfg!(feature = "memory")
+}
+
+/// Turns the accounting of parts on or off, only possible with the counting allocator
+/// installed. Counters are shared by the whole process, so the parts have to be solved
+/// one at a time for the numbers to mean anything
+pub fn track(on: bool) -> Result<()> {
+  if on && !is_installed() {
+    return Err(Error::InvalidInput("Memory accounting needs a build with the memory feature".to_string()));
+  }
+
+  TRACKING.store(on, Ordering::Relaxed);
+  Ok(())
+}
+
+pub fn is_tracking() -> bool {
+  TRACKING.load(Ordering::Relaxed)
+}
+
+/// Starts a measurement, the peak is counted again from the bytes live now
+pub fn start() -> Snapshot {
+  let live = LIVE.load(Ordering::Relaxed);
+  PEAK.store(live, Ordering::Relaxed);
+
+  Snapshot { allocations: ALLOCATIONS.load(Ordering::Relaxed), live }
+}
+
+/// Memory used since the snapshot was taken
+pub fn since(snapshot: &Snapshot) -> MemoryStats {
+  MemoryStats {
+    peak_bytes: PEAK.load(Ordering::Relaxed).saturating_sub(snapshot.live),
+    allocations: ALLOCATIONS.load(Ordering::Relaxed) - snapshot.allocations,
+    live_bytes: LIVE.load(Ordering::Relaxed).saturating_sub(snapshot.live)
+  }
+}
+
+/// Bytes in the largest binary unit they make at least one of
+pub fn format_bytes(bytes: usize) -> String {
+  const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+  if bytes < 1024 {
+    return format!("{} B", bytes);
+  }
+
+  let mut value = bytes as f64 / 1024.0;
+  let mut unit = 0;
+  while value >= 1024.0 && unit < UNITS.len() - 1 {
+    value /= 1024.0;
+    unit += 1;
+  }
+
+  format!("{:.1} {}", value, UNITS[unit])
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn bytes_are_formatted_in_binary_units() {
+    assert_eq!(format_bytes(0), "0 B");
+    assert_eq!(format_bytes(1023), "1023 B");
+    assert_eq!(format_bytes(1536), "1.5 KiB");
+    assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+  }
+
+  // Without the memory feature nothing else goes through the allocator, so the numbers are exact
+  #[cfg(not(feature = "memory"))]
+  #[test]
+  fn allocator_counts_what_it_hands_out() {
+    let layout = Layout::from_size_align(4096, 8).unwrap();
+    let snapshot = start();
+
+    // Safe, the memory is only freed with the layout it was allocated with
+    unsafe {
+      let ptr = CountingAllocator.alloc(layout);
+      assert!(!ptr.is_null());
+      let ptr = CountingAllocator.realloc(ptr, layout, 8192);
+      assert!(!ptr.is_null());
+      CountingAllocator.dealloc(ptr, Layout::from_size_align(8192, 8).unwrap());
+    }
+
+    assert_eq!(since(&snapshot), MemoryStats { peak_bytes: 8192, allocations: 2, live_bytes: 0 });
+  }
+
+  #[cfg(feature = "memory")]
+  #[test]
+  fn allocations_are_counted() {
+    let snapshot = start();
+    drop(std::hint::black_box(vec![0u8; 1 << 20]));
+    drop(std::hint::black_box(vec![0u8; 1 << 10]));
+    let stats = since(&snapshot);
+
+    // Other tests allocate at the same time, only what they can't take away is checked
+    assert!(stats.allocations >= 2);
+    assert!(stats.peak_bytes > 0);
+  }
+}
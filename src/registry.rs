@@ -0,0 +1,58 @@
+use std::any::Any;
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::solver::Puzzle;
+use crate::utils::{read_input, RawInput};
+
+#[cfg(feature = "day1")] use crate::day1;
+#[cfg(feature = "day2")] use crate::day2;
+#[cfg(feature = "day3")] use crate::day3;
+#[cfg(feature = "day4")] use crate::day4;
+#[cfg(feature = "day5")] use crate::day5;
+#[cfg(feature = "day7")] use crate::day7;
+#[cfg(feature = "day8")] use crate::day8;
+#[cfg(feature = "day9")] use crate::day9;
+
+/// A day registered with the runner
+#[derive(Clone, Copy)]
+pub struct Day {
+  pub day: u8,
+  pub solver: &'static dyn Puzzle
+}
+
+impl Day {
+  /// Path of the puzzle input used when no other input is given
+  pub fn default_input(&self) -> String {
+    Config::default().input(self.day)
+  }
+
+  /// Parses the input already read from the file, parse errors are reported against that file
+  pub fn parse(&self, input: &RawInput, path: &str) -> Result<Box<dyn Any + Send>> {
+    self.solver.parse_input(input).map_err(|e| e.in_file(path))
+  }
+
+  /// Reads and parses the input file, `-` reads the standard input
+  pub fn load(&self, path: &str) -> Result<Box<dyn Any + Send>> {
+    self.parse(&read_input(path)?, path)
+  }
+}
+
+/// All the days linked into the binary, in order. The `dayN` features only
+/// decide which of the modules get compiled in
+pub fn days() -> Vec<Day> {
+  vec![
+    #[cfg(feature = "day1")] Day { day: 1, solver: &day1::Day1 },
+    #[cfg(feature = "day2")] Day { day: 2, solver: &day2::Day2 },
+    #[cfg(feature = "day3")] Day { day: 3, solver: &day3::Day3 },
+    #[cfg(feature = "day4")] Day { day: 4, solver: &day4::Day4 },
+    #[cfg(feature = "day5")] Day { day: 5, solver: &day5::Day5 },
+    #[cfg(feature = "day7")] Day { day: 7, solver: &day7::Day7 },
+    #[cfg(feature = "day8")] Day { day: 8, solver: &day8::Day8 },
+    #[cfg(feature = "day9")] Day { day: 9, solver: &day9::Day9 },
+  ]
+}
+
+pub fn find(day: u8) -> Option<Day> {
+  days().into_iter().find(|d| d.day == day)
+}
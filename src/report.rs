@@ -0,0 +1,144 @@
+use serde::Serialize;
+
+use crate::error::{Error, Result};
+use crate::memory::MemoryStats;
+use crate::runner::{PartResult, Status};
+use crate::solver::Answer;
+
+/// Output formats of the run results
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+  /// One `DayN, partM: answer` line per part, failures go to stderr
+  Text,
+  /// A single JSON document with one record per day and part
+  Json,
+  /// Markdown table, ready to be pasted into a README
+  Markdown
+}
+
+impl Format {
+  pub fn from_name(name: &str) -> Option<Format> {
+    match name {
+      "text" => Some(Format::Text),
+      "json" => Some(Format::Json),
+      "markdown" | "md" => Some(Format::Markdown),
+      _ => None
+    }
+  }
+}
+
+#[derive(Serialize)]
+struct Record<'a> {
+  day: u8,
+  part: u8,
+  answer: Option<&'a Answer>,
+  error: Option<&'a str>,
+  timed_out: bool,
+  elapsed_ms: f64,
+  input: &'a str,
+  input_hash: Option<&'a str>,
+  cached: bool,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  memory: Option<MemoryStats>
+}
+
+#[derive(Serialize)]
+struct Document<'a> {
+  results: Vec<Record<'a>>
+}
+
+fn record(result: &PartResult) -> Record<'_> {
+  let (answer, error) = match &result.status {
+    Status::Solved(answer) => (Some(answer), None),
+    Status::Failed(e) => (None, Some(e.as_str())),
+    Status::TimedOut(_) => (None, None)
+  };
+
+  Record {
+    day: result.day,
+    part: result.part,
+    answer,
+    error,
+    timed_out: matches!(result.status, Status::TimedOut(_)),
+    elapsed_ms: result.elapsed.as_secs_f64() * 1000.0,
+    input: &result.input,
+    input_hash: result.input_hash.as_deref(),
+    cached: result.cached,
+    memory: result.memory
+  }
+}
+
+pub fn render_json(results: &[PartResult]) -> Result<String> {
+  let document = Document { results: results.iter().map(record).collect() };
+
+  serde_json::to_string_pretty(&document)
+    .map_err(|e| Error::InvalidInput(format!("Unable to serialize report: {}", e)))
+}
+
+fn escape_markdown(value: &str) -> String {
+  value.replace('|', "\\|")
+}
+
+pub fn render_markdown(results: &[PartResult]) -> String {
+  let mut table = String::from("| Day | Part | Answer | Time (ms) | Input |\n|----:|-----:|--------|----------:|-------|\n");
+
+  for result in results {
+    let answer = match &result.status {
+      Status::Solved(answer) if result.cached => format!("`{}` (cached)", answer),
+      Status::Solved(answer) => format!("`{}`", answer),
+      Status::Failed(e) => format!("**error:** {}", escape_markdown(e)),
+      Status::TimedOut(budget) => format!("**timed out** after {:?}", budget)
+    };
+
+    table += &format!(
+      "| {} | {} | {} | {:.3} | `{}` |\n",
+      result.day, result.part, answer, result.elapsed.as_secs_f64() * 1000.0, escape_markdown(&result.input)
+    );
+  }
+
+  table
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use std::time::Duration;
+
+  fn results() -> Vec<PartResult> {
+    vec![
+      PartResult {
+        day: 3, part: 2, input: "data/day3/input.txt".to_string(), input_hash: Some("00ff".to_string()),
+        elapsed: Duration::from_millis(2), status: Status::Solved(Answer::Number(331)), cached: false,
+        memory: Some(MemoryStats { peak_bytes: 2048, allocations: 3, live_bytes: 0 })
+      },
+      PartResult {
+        day: 5, part: 1, input: "data/day5/input.txt".to_string(), input_hash: None,
+        elapsed: Duration::default(), status: Status::Failed("Invalid input: a|b".to_string()), cached: false,
+        memory: None
+      }
+    ]
+  }
+
+  #[test]
+  fn json_report_has_record_per_part() {
+    let json: serde_json::Value = serde_json::from_str(&render_json(&results()).unwrap()).unwrap();
+
+    assert_eq!(json["results"][0]["day"], 3);
+    assert_eq!(json["results"][0]["part"], 2);
+    assert_eq!(json["results"][0]["answer"], 331);
+    assert_eq!(json["results"][0]["input_hash"], "00ff");
+    assert_eq!(json["results"][0]["memory"]["peak_bytes"], 2048);
+    assert_eq!(json["results"][1]["error"], "Invalid input: a|b");
+    assert!(json["results"][1]["answer"].is_null());
+    assert!(json["results"][1].get("memory").is_none());
+    assert_eq!(json["results"][1]["timed_out"], false);
+  }
+
+  #[test]
+  fn markdown_report_escapes_table_separators() {
+    let markdown = render_markdown(&results());
+
+    assert!(markdown.contains("| 3 | 2 | `331` | 2.000 | `data/day3/input.txt` |"));
+    assert!(markdown.contains("a\\|b"));
+  }
+}
@@ -0,0 +1,283 @@
+use std::any::Any;
+use std::sync::Mutex;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+
+use crate::cache::AnswerCache;
+use crate::cancel::Cancellation;
+use crate::error::Result;
+use crate::memory::{self, MemoryStats};
+use crate::registry::Day;
+use crate::solver::{Answer, Context};
+use crate::utils::{read_input, RawInput};
+
+/// What happened when a part was run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Status {
+  Solved(Answer),
+  Failed(String),
+  /// The part ran out of its time budget
+  TimedOut(Duration)
+}
+
+/// Result of running a single part of a day against an input
+#[derive(Debug, Clone)]
+pub struct PartResult {
+  pub day: u8,
+  pub part: u8,
+  pub input: String,
+  /// Hash of the normalized input content, `None` when the input couldn't be read
+  pub input_hash: Option<String>,
+  /// Time spent solving the part, parsing the input is not included
+  pub elapsed: Duration,
+  pub status: Status,
+  /// The answer was taken from the cache instead of solving the part
+  pub cached: bool,
+  /// Memory used while solving the part, only measured when memory accounting is on
+  pub memory: Option<MemoryStats>
+}
+
+impl PartResult {
+  pub fn is_failure(&self) -> bool {
+    match self.status {
+      Status::Solved(_) => false,
+      Status::Failed(_) | Status::TimedOut(_) => true
+    }
+  }
+}
+
+/// Parses the input once and runs every selected part against it
+pub fn run_day(day: &Day, path: &str, parts: &[u8], ctx: &Context) -> Vec<PartResult> {
+  solve_parts(day, path, read_input(path), parts, ctx)
+}
+
+/// Same as `run_day`, but parts with an answer in the cache are not solved again. The
+/// input isn't even parsed when all the parts are cached
+pub fn run_day_cached(day: &Day, path: &str, parts: &[u8], ctx: &Context, cache: &AnswerCache) -> Vec<PartResult> {
+  let raw = read_input(path);
+  let input_hash = raw.as_ref().ok().map(RawInput::hash);
+  let cached = |part: u8| input_hash.as_ref()
+    .and_then(|hash| cache.get(&AnswerCache::key(day.day, part, hash, day.solver.version(), strategy(day, part, ctx), &ctx.params)))
+    .map(|answer| PartResult {
+      day: day.day, part, input: path.to_string(), input_hash: input_hash.clone(),
+      elapsed: Duration::default(), status: Status::Solved(answer.clone()), cached: true, memory: None
+    });
+
+  let (hits, misses): (Vec<u8>, Vec<u8>) = parts.iter().partition(|part| cached(**part).is_some());
+  let mut solved = if misses.is_empty() { Vec::new() } else { solve_parts(day, path, raw, &misses, ctx) }.into_iter();
+
+  parts.iter()
+    .filter_map(|part| if hits.contains(part) { cached(*part) } else { solved.next() })
+    .collect()
+}
+
+/// Key the result would be cached under, `None` if it shouldn't be cached
+pub fn cache_key(result: &PartResult, day: &Day, ctx: &Context) -> Option<String> {
+  match (&result.status, &result.input_hash, result.cached) {
+    (Status::Solved(_), Some(hash), false) =>
+      Some(AnswerCache::key(result.day, result.part, hash, day.solver.version(), strategy(day, result.part, ctx), &ctx.params)),
+    _ => None
+  }
+}
+
+/// Strategy the part is solved with, parts without alternatives ignore the one in the context
+fn strategy<'a>(day: &Day, part: u8, ctx: &'a Context) -> Option<&'a str> {
+  ctx.strategy.as_deref().filter(|_| !day.solver.strategies(part).is_empty())
+}
+
+fn solve_parts(day: &Day, path: &str, raw: Result<RawInput>, parts: &[u8], ctx: &Context) -> Vec<PartResult> {
+  let input_hash = raw.as_ref().ok().map(RawInput::hash);
+  let result = |part: u8, elapsed: Duration, status: Status, memory: Option<MemoryStats>| PartResult {
+    day: day.day, part, input: path.to_string(), input_hash: input_hash.clone(), elapsed, status, cached: false, memory
+  };
+
+  match raw.and_then(|raw| day.parse(&raw, path)) {
+    Ok(input) => parts.iter().map(|part| {
+      let start = Instant::now();
+      let (status, memory) = solve_within_budget(day, *part, &*input, ctx);
+      let elapsed = start.elapsed();
+
+      result(*part, elapsed, status, memory)
+    }).collect(),
+    Err(e) => parts.iter()
+      .map(|part| result(*part, Duration::default(), Status::Failed(e.to_string()), None))
+      .collect()
+  }
+}
+
+/// Solves the part, cancelling it once it runs out of the budget of the context. Every
+/// part gets its own cancellation token, parts of the same day share the context. Parts
+/// over the budget time out even when the solver didn't stop and found the answer.
+/// With memory accounting on, only the memory of the solver is measured, not the watchdog
+pub(crate) fn solve_within_budget(day: &Day, part: u8, input: &dyn Any, ctx: &Context) -> (Status, Option<MemoryStats>) {
+  let status = |result: Result<Answer>| match result {
+    Ok(answer) => Status::Solved(answer),
+    Err(e) => Status::Failed(e.to_string())
+  };
+  let solve = |ctx: &Context| {
+    let snapshot = memory::is_tracking().then(memory::start);
+    let result = day.solver.solve(part, input, ctx);
+    (result, snapshot.as_ref().map(memory::since))
+  };
+
+  let budget = match ctx.budget {
+    Some(budget) => budget,
+    None => {
+      let (result, memory) = solve(ctx);
+      return (status(result), memory);
+    }
+  };
+
+  let cancel = Cancellation::new();
+  let ctx = ctx.clone().with_cancel(cancel.clone());
+  let (done, finished) = mpsc::channel::<()>();
+
+  let (result, memory) = thread::scope(|scope| {
+    let watchdog = cancel.clone();
+    scope.spawn(move || if let Err(RecvTimeoutError::Timeout) = finished.recv_timeout(budget) {
+      watchdog.cancel();
+    });
+
+    let solved = solve(&ctx);
+    drop(done);
+    solved
+  });
+
+  (if cancel.is_cancelled() { Status::TimedOut(budget) } else { status(result) }, memory)
+}
+
+/// A single part of a day to run against an input, with its own context so days can
+/// be given different parameters
+pub struct Job {
+  pub day: Day,
+  pub part: u8,
+  pub input: String,
+  pub ctx: Context
+}
+
+/// Number of workers used when none is given, one per available CPU
+pub fn default_workers() -> usize {
+  thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Runs the jobs on a pool of `workers` threads. Every job parses its own input, so
+/// parts of the same day can run in parallel too. Results are returned in the order
+/// of the jobs, no matter which one finishes first. Answers found in the cache, when
+/// one is given, are not solved again. With memory accounting on, the jobs run one at a
+/// time, so the memory of a part isn't mixed with the others
+pub fn run_parallel(jobs: &[Job], workers: usize, cache: Option<&AnswerCache>) -> Vec<PartResult> {
+  let workers = if memory::is_tracking() { 1 } else { workers };
+
+  run_pool(jobs, workers, |job| match cache {
+    Some(cache) => run_day_cached(&job.day, &job.input, &[job.part], &job.ctx, cache),
+    None => run_day(&job.day, &job.input, &[job.part], &job.ctx)
+  }.pop())
+}
+
+/// Runs `run` for every job on a pool of `workers` threads, results keep the order of the jobs
+pub(crate) fn run_pool<F>(jobs: &[Job], workers: usize, run: F) -> Vec<PartResult>
+  where F: Fn(&Job) -> Option<PartResult> + Sync {
+  let queue = Mutex::new(jobs.iter().enumerate());
+  let results: Mutex<Vec<Option<PartResult>>> = Mutex::new(vec![None; jobs.len()]);
+
+  thread::scope(|scope| {
+    for _ in 0 .. workers.clamp(1, jobs.len().max(1)) {
+      scope.spawn(|| loop {
+        let next = queue.lock().unwrap().next();
+        match next {
+          Some((i, job)) => {
+            let result = run(job);
+            results.lock().unwrap()[i] = result;
+          },
+          None => break
+        }
+      });
+    }
+  });
+
+  results.into_inner().unwrap().into_iter().flatten().collect()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::registry;
+
+  #[test]
+  fn parallel_results_keep_job_order() {
+    let jobs: Vec<Job> = registry::days().into_iter()
+      .flat_map(|day| {
+        let input = day.default_input();
+        (1 ..= 2).map(move |part| Job { day, part, input: input.clone(), ctx: Context::default() })
+      })
+      .filter(|job| job.day.day != 9)
+      .collect();
+
+    let sequential: Vec<(u8, u8, Status)> = jobs.iter()
+      .flat_map(|job| run_day(&job.day, &job.input, &[job.part], &job.ctx))
+      .map(|r| (r.day, r.part, r.status))
+      .collect();
+    let parallel: Vec<(u8, u8, Status)> = run_parallel(&jobs, 4, None).into_iter()
+      .map(|r| (r.day, r.part, r.status))
+      .collect();
+
+    assert_eq!(parallel, sequential);
+  }
+
+  #[cfg(feature = "day5")]
+  #[test]
+  fn cached_answers_are_not_solved_again() {
+    let day = registry::find(5).expect("Day 5 is not registered");
+    let path = "data/day5/example.txt";
+    let ctx = Context::default();
+    let solved = run_day(&day, path, &[1, 2], &ctx);
+
+    let mut cache = AnswerCache::default();
+    let key = cache_key(&solved[1], &day, &ctx).expect("Solved part should be cached");
+    cache.insert(key, Answer::Number(-1));
+    let results = run_day_cached(&day, path, &[1, 2], &ctx, &cache);
+
+    assert_eq!((results[0].status.clone(), results[0].cached), (solved[0].status.clone(), false));
+    assert_eq!((results[1].status.clone(), results[1].cached), (Status::Solved(Answer::Number(-1)), true));
+  }
+
+  #[cfg(feature = "day3")]
+  #[test]
+  fn cached_answers_are_kept_per_strategy() {
+    let day = registry::find(3).expect("Day 3 is not registered");
+    let path = "data/day3/example.txt";
+    let strategy = |name: &str| Context::default().with_strategy(Some(name.to_string()));
+    let solved = run_day(&day, path, &[1], &strategy("grid"));
+
+    let mut cache = AnswerCache::default();
+    let key = cache_key(&solved[0], &day, &strategy("grid")).expect("Solved part should be cached");
+    cache.insert(key, Answer::Number(-1));
+
+    let grid = run_day_cached(&day, path, &[1], &strategy("grid"), &cache);
+    let overlap = run_day_cached(&day, path, &[1], &strategy("overlap"), &cache);
+    let unknown = run_day_cached(&day, path, &[1], &strategy("bogus"), &cache);
+
+    assert_eq!((grid[0].status.clone(), grid[0].cached), (Status::Solved(Answer::Number(-1)), true));
+    assert_eq!((overlap[0].status.clone(), overlap[0].cached), (solved[0].status.clone(), false));
+    assert!(matches!(&unknown[0].status, Status::Failed(message) if message.contains("Unknown strategy bogus")));
+  }
+
+  #[cfg(feature = "day9")]
+  #[test]
+  fn parts_over_budget_time_out() {
+    use crate::solver::Params;
+
+    let day = registry::find(9).expect("Day 9 is not registered");
+    let mut params = Params::new();
+    params.set("last_marble", 200_000);
+    let ctx = Context::with_params(params).with_budget(Some(Duration::from_millis(1)));
+
+    let results = run_day(&day, "data/day9/example1.txt", &[2], &ctx);
+
+    assert_eq!(results[0].status, Status::TimedOut(Duration::from_millis(1)));
+    assert!(results[0].is_failure());
+    assert!(cache_key(&results[0], &day, &ctx).is_none());
+  }
+}
@@ -0,0 +1,203 @@
+use std::fs;
+use std::path::Path;
+use regex::Regex;
+
+use crate::error::{Error, Result};
+
+lazy_static! {
+  static ref FEATURE_LINE: Regex = Regex::new(r"^day([0-9]+) = \[\]$").unwrap();
+  static ref ALL_FEATURE: Regex = Regex::new(r"(?m)^all = \[(.*)\]$").unwrap();
+  static ref MOD_LINE: Regex = Regex::new(r#"^#\[cfg\(feature = "day([0-9]+)"\)\] pub mod "#).unwrap();
+  static ref USE_LINE: Regex = Regex::new(r#"^#\[cfg\(feature = "day([0-9]+)"\)\] use crate::"#).unwrap();
+  static ref REGISTRY_LINE: Regex = Regex::new(r#"^\s*#\[cfg\(feature = "day([0-9]+)"\)\] Day \{"#).unwrap();
+}
+
+fn module_template(day: u8) -> String {
+  format!(r#"use crate::error::{{Error, Result}};
+use crate::solver::{{Answer, Context, Solver}};
+use crate::utils::RawInput;
+
+pub struct Day{day};
+
+impl Solver for Day{day} {{
+  type Input = Vec<String>;
+
+  fn parse(&self, input: &RawInput) -> Result<Vec<String>> {{
+    Ok(input.lines().map(String::from).collect())
+  }}
+
+  fn part1(&self, _input: &Vec<String>, _ctx: &Context) -> Result<Answer> {{
+    Err(Error::NoSolution("Part 1 is not solved yet".to_string()))
+  }}
+
+  fn part2(&self, _input: &Vec<String>, _ctx: &Context) -> Result<Answer> {{
+    Err(Error::NoSolution("Part 2 is not solved yet".to_string()))
+  }}
+}}
+
+#[cfg(test)]
+mod test {{
+  use super::*;
+
+  #[test]
+  fn example_is_parsed() {{
+    let input = RawInput::from_text(include_str!("../data/day{day}/input_example.txt"));
+
+    assert!(Day{day}.parse(&input).is_ok());
+  }}
+}}
+"#, day = day)
+}
+
+const EXAMPLE_SIDECAR: &str = "# Expected answers of input_example.txt, checked by the examples test once set
+# part1 = 0
+# part2 = 0
+";
+
+fn read(path: &Path) -> Result<String> {
+  fs::read_to_string(path).map_err(|source| Error::Io { path: path.display().to_string(), source })
+}
+
+fn write(path: &Path, content: &str) -> Result<()> {
+  fs::write(path, content).map_err(|source| Error::Io { path: path.display().to_string(), source })
+}
+
+/// Inserts the line among the lines registering days, keeping them ordered by day
+fn insert_line(content: &str, matcher: &Regex, day: u8, line: &str) -> Result<String> {
+  let lines: Vec<&str> = content.lines().collect();
+  let registered: Vec<(usize, u8)> = lines.iter().enumerate()
+    .filter_map(|(i, l)| matcher.captures(l).and_then(|c| c[1].parse::<u8>().ok()).map(|d| (i, d)))
+    .collect();
+
+  if registered.iter().any(|(_, d)| *d == day) {
+    return Err(Error::InvalidInput(format!("Day {} is already registered", day)));
+  }
+
+  let position = match (registered.iter().rev().find(|(_, d)| *d < day), registered.first()) {
+    (Some((i, _)), _) => i + 1,
+    (None, Some((i, _))) => *i,
+    (None, None) => return Err(Error::InvalidInput(format!("No registered days found matching {}", matcher.as_str())))
+  };
+
+  let mut result: Vec<&str> = lines[.. position].to_vec();
+  result.push(line);
+  result.extend_from_slice(&lines[position ..]);
+  Ok(result.join("\n") + "\n")
+}
+
+/// Adds the day to the `all` feature, keeping the list ordered by day
+fn add_to_all_feature(content: &str, day: u8) -> Result<String> {
+  let captures = ALL_FEATURE.captures(content)
+    .ok_or_else(|| Error::InvalidInput("Cargo.toml has no all feature".to_string()))?;
+  let mut days: Vec<u8> = captures[1].split(',')
+    .filter_map(|f| f.trim().trim_matches('"').strip_prefix("day").and_then(|d| d.parse::<u8>().ok()))
+    .collect();
+  days.push(day);
+  days.sort_unstable();
+
+  let features: Vec<String> = days.iter().map(|d| format!("\"day{}\"", d)).collect();
+  Ok(ALL_FEATURE.replace(content, format!("all = [{}]", features.join(", ")).as_str()).to_string())
+}
+
+/// Generates the module of a new day under `root`, with solver stubs and a test module, creates
+/// its data directory with a placeholder example and registers it with the feature, the crate
+/// and the runner. Returns the paths of the created and updated files
+pub fn new_day(root: &Path, day: u8) -> Result<Vec<String>> {
+  if !(1 ..= 25).contains(&day) {
+    return Err(Error::InvalidInput(format!("Day has to be between 1 and 25, got {}", day)));
+  }
+
+  let module = root.join("src").join(format!("day{}.rs", day));
+  if module.exists() {
+    return Err(Error::InvalidInput(format!("{} already exists", module.display())));
+  }
+
+  // Every file is updated in memory first, so a day that can't be registered leaves nothing behind
+  let cargo_path = root.join("Cargo.toml");
+  let lib_path = root.join("src").join("lib.rs");
+  let registry_path = root.join("src").join("registry.rs");
+
+  let cargo = insert_line(&read(&cargo_path)?, &FEATURE_LINE, day, &format!("day{} = []", day))?;
+  let cargo = add_to_all_feature(&cargo, day)?;
+  let lib = insert_line(
+    &read(&lib_path)?, &MOD_LINE, day, &format!("#[cfg(feature = \"day{0}\")] pub mod day{0};", day)
+  )?;
+  let registry = insert_line(
+    &read(&registry_path)?, &USE_LINE, day, &format!("#[cfg(feature = \"day{0}\")] use crate::day{0};", day)
+  )?;
+  let registry = insert_line(
+    &registry, &REGISTRY_LINE, day, &format!("    #[cfg(feature = \"day{0}\")] Day {{ day: {0}, solver: &day{0}::Day{0} }},", day)
+  )?;
+
+  let data_dir = root.join("data").join(format!("day{}", day));
+  fs::create_dir_all(&data_dir).map_err(|source| Error::Io { path: data_dir.display().to_string(), source })?;
+
+  let example = data_dir.join("input_example.txt");
+  let sidecar = data_dir.join("input_example.expected.toml");
+  let mut files = vec![(module, module_template(day))];
+  if !example.exists() {
+    files.push((example, String::new()));
+    files.push((sidecar, EXAMPLE_SIDECAR.to_string()));
+  }
+  files.push((cargo_path, cargo));
+  files.push((lib_path, lib));
+  files.push((registry_path, registry));
+
+  for (path, content) in &files {
+    write(path, content)?;
+  }
+
+  Ok(files.iter().map(|(path, _)| path.display().to_string()).collect())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn day_is_inserted_in_order() {
+    let lib = "extern crate regex;\n\n#[cfg(feature = \"day5\")] pub mod day5;\n#[cfg(feature = \"day7\")] pub mod day7;\n\npub mod bench;\n";
+    let inserted = insert_line(lib, &MOD_LINE, 6, "#[cfg(feature = \"day6\")] pub mod day6;").unwrap();
+
+    assert_eq!(
+      inserted,
+      "extern crate regex;\n\n#[cfg(feature = \"day5\")] pub mod day5;\n#[cfg(feature = \"day6\")] pub mod day6;\n#[cfg(feature = \"day7\")] pub mod day7;\n\npub mod bench;\n"
+    );
+    assert!(insert_line(&inserted, &MOD_LINE, 6, "").is_err());
+  }
+
+  #[test]
+  fn new_day_is_scaffolded_into_a_copy_of_the_tree() {
+    let root = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+    fs::create_dir_all(root.join("src")).unwrap();
+    for file in &["Cargo.toml", "src/lib.rs", "src/registry.rs"] {
+      fs::copy(file, root.join(file)).unwrap();
+    }
+
+    let written = new_day(&root, 6).expect("Unable to scaffold day");
+    let content = |file: &str| fs::read_to_string(root.join(file)).unwrap();
+
+    assert_eq!(written.len(), 6);
+    assert!(content("src/day6.rs").contains("impl Solver for Day6"));
+    assert!(root.join("data/day6/input_example.txt").exists());
+    assert!(content("data/day6/input_example.expected.toml").contains("part1"));
+    assert!(content("Cargo.toml").contains("\nday6 = []\n"));
+    assert!(ALL_FEATURE.captures(&content("Cargo.toml")).unwrap()[1].contains("\"day6\""));
+    assert!(content("src/lib.rs").contains("#[cfg(feature = \"day6\")] pub mod day6;"));
+    assert!(content("src/registry.rs").contains("#[cfg(feature = \"day6\")] use crate::day6;"));
+    assert!(content("src/registry.rs").contains("#[cfg(feature = \"day6\")] Day { day: 6, solver: &day6::Day6 },"));
+    assert!(new_day(&root, 6).is_err());
+
+    fs::remove_dir_all(&root).unwrap();
+  }
+
+  #[test]
+  fn day_is_added_to_all_feature() {
+    let cargo = "[features]\nday1 = []\nday7 = []\nall = [\"day1\", \"day7\"]\ndefault = [\"all\"]\n";
+
+    assert_eq!(
+      add_to_all_feature(cargo, 6).unwrap(),
+      "[features]\nday1 = []\nday7 = []\nall = [\"day1\", \"day6\", \"day7\"]\ndefault = [\"all\"]\n"
+    );
+  }
+}
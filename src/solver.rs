@@ -0,0 +1,230 @@
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+use crate::cancel::Cancellation;
+use crate::error::{Error, Result};
+use crate::trace::Trace;
+use crate::utils::RawInput;
+
+/// Answer to a single part of a puzzle. Most of the puzzles are answered with a number,
+/// a few of them with a piece of text
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Answer {
+  Number(i64),
+  Text(String)
+}
+
+impl fmt::Display for Answer {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Answer::Number(n) => write!(f, "{}", n),
+      Answer::Text(t) => write!(f, "{}", t)
+    }
+  }
+}
+
+macro_rules! answer_from_number {
+  ($($t:ty),*) => {
+    $(
+      impl From<$t> for Answer {
+        fn from(value: $t) -> Answer {
+          Answer::Number(i64::from(value))
+        }
+      }
+    )*
+  };
+}
+
+/// Numbers that may not fit into the answer are rejected instead of wrapping around
+macro_rules! answer_try_from_number {
+  ($($t:ty),*) => {
+    $(
+      impl TryFrom<$t> for Answer {
+        type Error = Error;
+
+        fn try_from(value: $t) -> Result<Answer> {
+          i64::try_from(value)
+            .map(Answer::Number)
+            .map_err(|_| Error::InvalidInput(format!("Answer {} is too large", value)))
+        }
+      }
+    )*
+  };
+}
+
+answer_from_number!(i32, i64, u32);
+answer_try_from_number!(u64, usize);
+
+impl From<String> for Answer {
+  fn from(value: String) -> Answer {
+    Answer::Text(value)
+  }
+}
+
+impl From<&str> for Answer {
+  fn from(value: &str) -> Answer {
+    Answer::Text(value.to_string())
+  }
+}
+
+/// Named numeric parameters of a puzzle, like the number of workers in day 7
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Params {
+  values: BTreeMap<String, i64>
+}
+
+impl Params {
+  pub fn new() -> Params {
+    Params::default()
+  }
+
+  pub fn set(&mut self, name: &str, value: i64) {
+    self.values.insert(name.to_string(), value);
+  }
+
+  pub fn get(&self, name: &str) -> Option<i64> {
+    self.values.get(name).cloned()
+  }
+
+  /// Returns the parameter converted to the type the solver needs, or the default
+  /// when it isn't set. Values that don't fit the type are reported as invalid input
+  pub fn get_or<T: TryFrom<i64>>(&self, name: &str, default: T) -> Result<T> {
+    match self.get(name) {
+      None => Ok(default),
+      Some(value) => T::try_from(value)
+        .map_err(|_| Error::InvalidInput(format!("Parameter {} is out of range: {}", name, value)))
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.values.is_empty()
+  }
+}
+
+/// Everything a solver can use besides its input
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+  pub params: Params,
+  /// Name of the strategy to solve the parts with, the default one when not set
+  pub strategy: Option<String>,
+  /// Where the solver explains the steps it takes, disabled unless running with `--explain`
+  pub trace: Trace,
+  /// Time a part may take before the runner cancels it and reports a timeout
+  pub budget: Option<Duration>,
+  /// Cancelled by the runner once the budget runs out, long loops should check it
+  pub cancel: Cancellation
+}
+
+impl Context {
+  pub fn with_params(params: Params) -> Context {
+    Context { params, ..Context::default() }
+  }
+
+  pub fn with_strategy(self, strategy: Option<String>) -> Context {
+    Context { strategy, ..self }
+  }
+
+  pub fn with_trace(self, trace: Trace) -> Context {
+    Context { trace, ..self }
+  }
+
+  pub fn with_budget(self, budget: Option<Duration>) -> Context {
+    Context { budget, ..self }
+  }
+
+  pub fn with_cancel(self, cancel: Cancellation) -> Context {
+    Context { cancel, ..self }
+  }
+}
+
+/// One of the alternative implementations of a part, selected by its name
+pub struct Strategy<I> {
+  pub name: &'static str,
+  pub solve: fn(&I, &Context) -> Result<Answer>
+}
+
+/// Every day is implemented as a solver that first parses the puzzle input into
+/// its own domain representation, which is then shared by both of the parts.
+/// Solvers and their inputs have to be safe to move between threads, so the runner
+/// can solve days in parallel
+pub trait Solver: Send + Sync {
+  type Input: Send + 'static;
+
+  /// Identifies the logic of the solver in the answer cache, bump it whenever a change
+  /// could give a different answer for the same input
+  const VERSION: &'static str = "1";
+
+  fn parse(&self, input: &RawInput) -> Result<Self::Input>;
+  fn part1(&self, input: &Self::Input, ctx: &Context) -> Result<Answer>;
+  fn part2(&self, input: &Self::Input, ctx: &Context) -> Result<Answer>;
+
+  /// Alternative implementations of the part. `part1` and `part2` stay the default, the
+  /// strategies are only used when one is selected by name in the context
+  fn strategies(&self, _part: u8) -> Vec<Strategy<Self::Input>> {
+    Vec::new()
+  }
+}
+
+/// Type-erased version of the `Solver`, allows solvers of different days
+/// to be kept together and run the same way
+pub trait Puzzle: Send + Sync {
+  fn parse_input(&self, input: &RawInput) -> Result<Box<dyn Any + Send>>;
+  fn solve(&self, part: u8, input: &dyn Any, ctx: &Context) -> Result<Answer>;
+  fn version(&self) -> &'static str;
+  /// Names of the strategies of the part, empty when it has a single implementation
+  fn strategies(&self, part: u8) -> Vec<&'static str>;
+}
+
+impl<S: Solver> Puzzle for S {
+  fn parse_input(&self, input: &RawInput) -> Result<Box<dyn Any + Send>> {
+    Ok(Box::new(Solver::parse(self, input)?))
+  }
+
+  fn solve(&self, part: u8, input: &dyn Any, ctx: &Context) -> Result<Answer> {
+    let input = input.downcast_ref::<S::Input>()
+      .unwrap_or_else(|| panic!("Input was not produced by this solver"));
+
+    // Parts without alternatives ignore the strategy, so it can be given when running all the days
+    let strategies = Solver::strategies(self, part);
+    if let (Some(name), false) = (&ctx.strategy, strategies.is_empty()) {
+      let names: Vec<&str> = strategies.iter().map(|s| s.name).collect();
+
+      return match strategies.iter().find(|s| s.name == name) {
+        Some(strategy) => (strategy.solve)(input, ctx),
+        None => Err(Error::InvalidInput(format!("Unknown strategy {} of part {}, available: {}", name, part, names.join(", "))))
+      };
+    }
+
+    match part {
+      1 => self.part1(input, ctx),
+      2 => self.part2(input, ctx),
+      _ => panic!("Unknown part: {}", part)
+    }
+  }
+
+  fn version(&self) -> &'static str {
+    S::VERSION
+  }
+
+  fn strategies(&self, part: u8) -> Vec<&'static str> {
+    Solver::strategies(self, part).iter().map(|s| s.name).collect()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn numbers_too_large_for_an_answer_are_rejected() {
+    assert_eq!(Answer::try_from(u64::MAX >> 1).unwrap(), Answer::Number(i64::MAX));
+    assert!(matches!(Answer::try_from(u64::MAX), Err(Error::InvalidInput(_))));
+    assert_eq!(Answer::from(u32::MAX), Answer::Number(4_294_967_295));
+  }
+}
@@ -0,0 +1,223 @@
+use std::fs;
+use std::path::Path;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::fetch::{agent, missing_session, USER_AGENT};
+use crate::solver::Answer;
+use crate::utils::read_toml;
+
+pub const DEFAULT_LEDGER_PATH: &str = "ledger.toml";
+
+/// What the puzzle server said about a submitted answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+  Correct,
+  TooHigh,
+  TooLow,
+  /// Wrong, without a hint in which direction
+  Wrong,
+  /// Submitted before the wait time after a wrong answer has passed
+  TooSoon,
+  /// The part is already solved, or not unlocked yet
+  AlreadySolved,
+  /// Response couldn't be understood
+  Unknown
+}
+
+impl Verdict {
+  /// Answers with these verdicts are known to be wrong and shouldn't be submitted again
+  pub fn is_wrong(self) -> bool {
+    matches!(self, Verdict::TooHigh | Verdict::TooLow | Verdict::Wrong)
+  }
+}
+
+/// Text of the `<article>` of the response page, with the markup stripped
+pub fn response_text(body: &str) -> String {
+  let article = match (body.find("<article"), body.find("</article>")) {
+    (Some(start), Some(end)) if start < end => &body[start .. end],
+    _ => body
+  };
+  let mut text = String::new();
+  let mut in_tag = false;
+
+  for c in article.chars() {
+    match c {
+      '<' => in_tag = true,
+      '>' => in_tag = false,
+      c if !in_tag => text.push(c),
+      _ => {}
+    }
+  }
+
+  text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+pub fn parse_verdict(body: &str) -> Verdict {
+  let text = response_text(body);
+
+  if text.contains("That's the right answer") {
+    Verdict::Correct
+  } else if text.contains("That's not the right answer") {
+    if text.contains("too high") {
+      Verdict::TooHigh
+    } else if text.contains("too low") {
+      Verdict::TooLow
+    } else {
+      Verdict::Wrong
+    }
+  } else if text.contains("You gave an answer too recently") {
+    Verdict::TooSoon
+  } else if text.contains("You don't seem to be solving the right level") {
+    Verdict::AlreadySolved
+  } else {
+    Verdict::Unknown
+  }
+}
+
+/// A single submitted answer, `time` is in RFC 3339 format
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attempt {
+  pub day: u8,
+  pub part: u8,
+  pub answer: Answer,
+  pub verdict: Verdict,
+  pub time: String
+}
+
+/// Every answer submitted so far, kept so known-wrong answers are never submitted twice
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Ledger {
+  #[serde(default)]
+  pub attempt: Vec<Attempt>
+}
+
+impl Ledger {
+  /// Loads the ledger, a missing file is an empty ledger
+  pub fn load(path: &str) -> Result<Ledger> {
+    if Path::new(path).exists() { read_toml(path) } else { Ok(Ledger::default()) }
+  }
+
+  pub fn save(&self, path: &str) -> Result<()> {
+    let content = toml::to_string(self)
+      .map_err(|e| Error::InvalidInput(format!("Unable to serialize ledger: {}", e)))?;
+
+    fs::write(path, content).map_err(|source| Error::Io { path: path.to_string(), source })
+  }
+
+  pub fn attempts(&self, day: u8, part: u8) -> impl Iterator<Item = &Attempt> {
+    self.attempt.iter().filter(move |a| a.day == day && a.part == part)
+  }
+
+  /// Range the answer has to fall in, exclusive on both ends, narrowed by the
+  /// answers that were too low or too high
+  pub fn bounds(&self, day: u8, part: u8) -> (Option<i64>, Option<i64>) {
+    self.attempts(day, part).fold((None, None), |(low, high), attempt| match (&attempt.answer, attempt.verdict) {
+      (Answer::Number(n), Verdict::TooLow) => (low.max(Some(*n)), high),
+      (Answer::Number(n), Verdict::TooHigh) => (low, Some(high.map_or(*n, |h: i64| h.min(*n)))),
+      _ => (low, high)
+    })
+  }
+
+  /// Reason not to submit the answer, if the ledger already knows how it would go
+  pub fn refusal(&self, day: u8, part: u8, answer: &Answer) -> Option<String> {
+    if let Some(correct) = self.attempts(day, part).find(|a| a.verdict == Verdict::Correct) {
+      return Some(format!("Day{}, part{} is already solved with {}", day, part, correct.answer));
+    }
+    if let Some(wrong) = self.attempts(day, part).find(|a| a.verdict.is_wrong() && &a.answer == answer) {
+      return Some(format!("{} was already rejected on {} ({:?})", answer, wrong.time, wrong.verdict));
+    }
+
+    match (answer, self.bounds(day, part)) {
+      (Answer::Number(n), (Some(low), _)) if *n <= low => Some(format!("{} is too low, {} already was", n, low)),
+      (Answer::Number(n), (_, Some(high))) if *n >= high => Some(format!("{} is too high, {} already was", n, high)),
+      _ => None
+    }
+  }
+}
+
+/// Result of a submission, either refused by the ledger or answered by the server
+#[derive(Debug, PartialEq)]
+pub enum Submission {
+  Refused(String),
+  Answered { verdict: Verdict, message: String }
+}
+
+pub fn answer_url(base_url: &str, day: u8) -> String {
+  format!("{}/day/{}/answer", base_url.trim_end_matches('/'), day)
+}
+
+fn post(url: &str, session: &str, part: u8, answer: &Answer) -> Result<String> {
+  let http_error = |status: Option<u16>, message: String| Error::Http { url: url.to_string(), status, message };
+  let response = agent()
+    .post(url)
+    .set("Cookie", &format!("session={}", session.trim()))
+    .set("User-Agent", USER_AGENT)
+    .send_form(&[("level", &part.to_string()), ("answer", &answer.to_string())]);
+
+  match response {
+    Ok(response) => response.into_string().map_err(|e| http_error(None, e.to_string())),
+    Err(ureq::Error::Status(status, response)) => {
+      let body = response.into_string().unwrap_or_default();
+      Err(http_error(Some(status), response_text(&body)))
+    },
+    Err(ureq::Error::Transport(e)) => Err(http_error(None, e.to_string()))
+  }
+}
+
+/// Submits the answer unless the ledger refuses it, every answered submission is
+/// recorded in the ledger file
+pub fn submit(
+  day: u8, part: u8, answer: &Answer, config: &Config, session: Option<&str>, ledger_path: &str
+) -> Result<Submission> {
+  let mut ledger = Ledger::load(ledger_path)?;
+  if let Some(reason) = ledger.refusal(day, part, answer) {
+    return Ok(Submission::Refused(reason));
+  }
+
+  let url = answer_url(&config.base_url, day);
+  let session = session.ok_or_else(|| missing_session(&url))?;
+  let body = post(&url, session, part, answer)?;
+  let verdict = parse_verdict(&body);
+
+  ledger.attempt.push(Attempt { day, part, answer: answer.clone(), verdict, time: Utc::now().to_rfc3339() });
+  ledger.save(ledger_path)?;
+
+  Ok(Submission::Answered { verdict, message: response_text(&body) })
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn attempt(answer: i64, verdict: Verdict) -> Attempt {
+    Attempt { day: 1, part: 1, answer: Answer::Number(answer), verdict, time: "2018-12-01T05:00:00Z".to_string() }
+  }
+
+  #[test]
+  fn verdict_is_parsed_from_response_article() {
+    let page = |text: &str| format!("<html><main><article><p>{}</p></article></main></html>", text);
+
+    assert_eq!(parse_verdict(&page("That's the right answer! You are <em>one gold star</em> closer.")), Verdict::Correct);
+    assert_eq!(parse_verdict(&page("That's not the right answer; your answer is too high.")), Verdict::TooHigh);
+    assert_eq!(parse_verdict(&page("That's not the right answer; your answer is too low.")), Verdict::TooLow);
+    assert_eq!(parse_verdict(&page("That's not the right answer. If you're stuck...")), Verdict::Wrong);
+    assert_eq!(parse_verdict(&page("You gave an answer too recently; you have 42s left to wait.")), Verdict::TooSoon);
+    assert_eq!(parse_verdict("<html>Maintenance</html>"), Verdict::Unknown);
+  }
+
+  #[test]
+  fn ledger_refuses_known_wrong_and_out_of_bounds_answers() {
+    let ledger = Ledger { attempt: vec![attempt(100, Verdict::TooLow), attempt(500, Verdict::TooHigh), attempt(300, Verdict::Wrong)] };
+
+    assert_eq!(ledger.bounds(1, 1), (Some(100), Some(500)));
+    assert!(ledger.refusal(1, 1, &Answer::Number(300)).is_some());
+    assert!(ledger.refusal(1, 1, &Answer::Number(50)).is_some());
+    assert!(ledger.refusal(1, 1, &Answer::Number(500)).is_some());
+    assert!(ledger.refusal(1, 1, &Answer::Number(250)).is_none());
+    assert!(ledger.refusal(1, 2, &Answer::Number(50)).is_none());
+  }
+}
@@ -0,0 +1,103 @@
+use std::sync::{Arc, Mutex};
+use serde_json::{Map, Value};
+
+use crate::error::{Error, Result};
+
+/// Named value of an event
+pub type Field = (&'static str, Value);
+
+/// A single step a solver reported, nested under the last event of a lower depth
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+  pub depth: usize,
+  pub name: &'static str,
+  pub fields: Vec<Field>
+}
+
+/// Collects the events solvers emit while solving a part. A disabled trace, the default,
+/// ignores them without building their fields, so solvers can emit from their hot loops
+#[derive(Debug, Clone, Default)]
+pub struct Trace {
+  events: Option<Arc<Mutex<Vec<Event>>>>
+}
+
+impl Trace {
+  pub fn enabled() -> Trace {
+    Trace { events: Some(Arc::new(Mutex::new(Vec::new()))) }
+  }
+
+  pub fn is_enabled(&self) -> bool {
+    self.events.is_some()
+  }
+
+  /// Records the event, its fields are only built when the trace is enabled
+  pub fn emit<F: FnOnce() -> Vec<Field>>(&self, depth: usize, name: &'static str, fields: F) {
+    if let Some(events) = &self.events {
+      let event = Event { depth, name, fields: fields() };
+      events.lock().unwrap_or_else(|e| e.into_inner()).push(event);
+    }
+  }
+
+  /// Events recorded so far, in the order they were emitted
+  pub fn events(&self) -> Vec<Event> {
+    self.events.as_ref()
+      .map_or_else(Vec::new, |events| events.lock().unwrap_or_else(|e| e.into_inner()).clone())
+  }
+}
+
+fn render_value(value: &Value) -> String {
+  match value {
+    Value::String(s) => s.clone(),
+    other => other.to_string()
+  }
+}
+
+/// Renders the events as lines indented by their depth, fields as `name=value`
+pub fn render_text(events: &[Event], indent: usize) -> String {
+  events.iter()
+    .map(|event| {
+      let fields: Vec<String> = event.fields.iter().map(|(name, value)| format!(" {}={}", name, render_value(value))).collect();
+      format!("{}{}{}\n", "  ".repeat(indent + event.depth), event.name, fields.concat())
+    })
+    .collect()
+}
+
+/// Renders the events as JSON lines, one object per event with the day and part it belongs to
+pub fn render_json_lines(day: u8, part: u8, events: &[Event]) -> Result<String> {
+  events.iter()
+    .map(|event| {
+      let mut object = Map::new();
+      object.insert("day".to_string(), day.into());
+      object.insert("part".to_string(), part.into());
+      object.insert("depth".to_string(), event.depth.into());
+      object.insert("event".to_string(), event.name.into());
+      object.insert("fields".to_string(), Value::Object(event.fields.iter().map(|(n, v)| (n.to_string(), v.clone())).collect()));
+
+      serde_json::to_string(&object)
+        .map(|line| line + "\n")
+        .map_err(|e| Error::InvalidInput(format!("Unable to serialize trace: {}", e)))
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn events_are_only_recorded_when_enabled() {
+    let disabled = Trace::default();
+    disabled.emit(0, "tick", || panic!("Fields of a disabled trace are built"));
+    assert!(disabled.events().is_empty());
+
+    let trace = Trace::enabled();
+    trace.clone().emit(0, "tick", || vec![("time", 0.into())]);
+    trace.emit(1, "assign", || vec![("worker", 1.into()), ("step", "C".into())]);
+
+    assert_eq!(render_text(&trace.events(), 1), "  tick time=0\n    assign worker=1 step=C\n");
+    assert_eq!(
+      render_json_lines(7, 2, &trace.events()[1 ..]).unwrap(),
+      "{\"day\":7,\"depth\":1,\"event\":\"assign\",\"fields\":{\"step\":\"C\",\"worker\":1},\"part\":2}\n"
+    );
+  }
+}
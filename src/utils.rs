@@ -1,13 +1,207 @@
-use std::io::{BufReader, BufRead};
-use std::fs::{File};
-
-pub fn read_input(path: &str) -> Vec<String> {
-  let file: File = File::open(path).unwrap_or_else(|_| panic!("Unable to open file: {}", path));
-  let reader: BufReader<&File> = BufReader::new(&file);
-
-  reader
-    .lines()
-    .enumerate()
-    .map(|(i, r_val)| r_val.unwrap_or_else(|_| panic!("Unable to read line {} from file {}", i, path)))
-    .collect()
-}
\ No newline at end of file
+use std::io::{self, Read};
+use std::fs::{self, File};
+use std::ops::Range;
+use std::sync::OnceLock;
+use memmap2::Mmap;
+use serde::de::DeserializeOwned;
+
+use crate::error::{Error, Result};
+
+/// Path that stands for the standard input
+pub const STDIN: &str = "-";
+
+/// Files of at least this size are memory-mapped instead of read
+pub const MMAP_THRESHOLD: u64 = 1 << 20;
+
+#[derive(Debug)]
+enum Buffer {
+  Owned(String),
+  Mapped(Mmap)
+}
+
+/// Puzzle input as read from a file, before any solver parsed it. The text is
+/// normalized on the way in: a leading BOM is dropped, CRLF endings become LF
+/// and trailing blank lines are removed
+///
+/// Content is read once, or memory-mapped for large files, and handed out as
+/// slices, so solvers can parse it without copying every line
+#[derive(Debug)]
+pub struct RawInput {
+  buffer: Buffer,
+  /// Part of the buffer left after the normalization, always valid UTF-8
+  range: Range<usize>
+}
+
+impl RawInput {
+  pub fn from_text(text: &str) -> RawInput {
+    RawInput::from_string(text.to_string())
+  }
+
+  /// Input from raw bytes, which have to be valid UTF-8
+  pub fn from_bytes(bytes: &[u8]) -> Result<RawInput> {
+    std::str::from_utf8(bytes)
+      .map(RawInput::from_text)
+      .map_err(|e| Error::InvalidInput(format!("Input is not valid UTF-8: {}", e)))
+  }
+
+  fn from_string(text: String) -> RawInput {
+    let text = if text.contains('\r') { text.replace("\r\n", "\n") } else { text };
+    let range = normalized_range(&text);
+
+    RawInput { buffer: Buffer::Owned(text), range }
+  }
+
+  /// Maps the file into memory, content with CRLF endings is copied so it can be normalized.
+  /// The content is checked to be UTF-8 once, here, so `text` can hand it out as it is
+  fn map(file: &File, path: &str) -> Result<RawInput> {
+    // The mapping is read-only and private to this process. Puzzle inputs aren't
+    // expected to be modified while a solver runs
+    let map = unsafe { Mmap::map(file) }.map_err(|source| Error::Io { path: path.to_string(), source })?;
+    let text = std::str::from_utf8(&map)
+      .map_err(|e| Error::InvalidInput(format!("Input {} is not valid UTF-8: {}", path, e)))?;
+
+    if text.contains('\r') {
+      return Ok(RawInput::from_text(text));
+    }
+
+    let range = normalized_range(text);
+    Ok(RawInput { buffer: Buffer::Mapped(map), range })
+  }
+
+  /// The whole input
+  pub fn text(&self) -> &str {
+    match &self.buffer {
+      Buffer::Owned(text) => &text[self.range.clone()],
+      // Safe, the mapping was checked to be UTF-8 when it was created and the range
+      // is cut on char boundaries of the checked text
+      Buffer::Mapped(map) => unsafe { std::str::from_utf8_unchecked(&map[self.range.clone()]) }
+    }
+  }
+
+  /// Lines of the input, with surrounding whitespace trimmed
+  pub fn lines(&self) -> impl Iterator<Item = &str> {
+    self.text().lines().map(str::trim)
+  }
+
+  /// The only line of the input, for puzzles that come as a single line
+  pub fn single_line(&self) -> Result<&str> {
+    let mut lines = self.text().lines();
+
+    match (lines.next(), lines.next()) {
+      (None, _) => Err(Error::InvalidInput("Input is empty".to_string())),
+      (Some(line), None) => Ok(line.trim()),
+      (Some(_), Some(_)) => Err(Error::parse(1, "Expected a single line of input").at_line(2))
+    }
+  }
+
+  /// Hash of the normalized text, see `hash_bytes`
+  pub fn hash(&self) -> String {
+    hash_bytes(self.text().as_bytes())
+  }
+}
+
+/// Range of the text without a leading BOM and trailing whitespace
+fn normalized_range(text: &str) -> Range<usize> {
+  let start = if text.starts_with('\u{feff}') { '\u{feff}'.len_utf8() } else { 0 };
+
+  start .. start + text[start ..].trim_end().len()
+}
+
+/// Standard input can only be read once, so it's kept for all the parts and days using it
+fn read_stdin() -> io::Result<String> {
+  static CONTENT: OnceLock<io::Result<String>> = OnceLock::new();
+
+  match CONTENT.get_or_init(|| {
+    let mut content = String::new();
+    io::stdin().read_to_string(&mut content).map(|_| content)
+  }) {
+    Ok(content) => Ok(content.clone()),
+    Err(e) => Err(io::Error::new(e.kind(), e.to_string()))
+  }
+}
+
+/// Reads the puzzle input from the file, or from the standard input when the path is `-`
+pub fn read_input(path: &str) -> Result<RawInput> {
+  let io_error = |source| Error::Io { path: path.to_string(), source };
+
+  if path == STDIN {
+    return read_stdin().map(RawInput::from_string).map_err(io_error);
+  }
+
+  let file = File::open(path).map_err(io_error)?;
+  if file.metadata().map_err(io_error)?.len() >= MMAP_THRESHOLD {
+    RawInput::map(&file, path)
+  } else {
+    io::read_to_string(file).map(RawInput::from_string).map_err(io_error)
+  }
+}
+
+/// Reads and deserializes a TOML file, parse errors point at the offending line and column
+pub fn read_toml<T: DeserializeOwned>(path: &str) -> Result<T> {
+  let content = fs::read_to_string(path)
+    .map_err(|source| Error::Io { path: path.to_string(), source })?;
+
+  toml::from_str(&content).map_err(|e| {
+    let (line, column) = e.span().map_or((0, 0), |span| {
+      let before = &content[.. span.start];
+      let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+      (before.matches('\n').count() + 1, before[line_start ..].chars().count() + 1)
+    });
+
+    Error::parse(column, e.message()).at_line(line).in_file(path)
+  })
+}
+
+/// FNV-1a hash of the content, stable across platforms and Rust versions, used
+/// to identify puzzle inputs in reports
+pub fn hash_bytes(content: &[u8]) -> String {
+  let hash = content.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+    (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+  });
+
+  format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn raw_input_is_normalized() {
+    let input = RawInput::from_text("\u{feff}+1\r\n -2 \r\n\r\n\n");
+
+    assert_eq!(input.text(), "+1\n -2");
+    assert_eq!(input.lines().collect::<Vec<&str>>(), vec!["+1", "-2"]);
+    assert!(input.single_line().is_err());
+    assert_eq!(RawInput::from_text("dabAcCaCBAcCcaDA\n").single_line().unwrap(), "dabAcCaCBAcCcaDA");
+    assert!(RawInput::from_text("\n").single_line().is_err());
+  }
+
+  #[test]
+  fn large_input_is_memory_mapped() {
+    let path = std::env::temp_dir().join("aoc-large-input.txt");
+    let lines = MMAP_THRESHOLD as usize / 3 + 1;
+    fs::write(&path, format!("\u{feff}{}\n\n", vec!["+1"; lines].join("\n"))).unwrap();
+    let input = read_input(&path.to_string_lossy()).expect("Unable to read input");
+
+    assert!(matches!(input.buffer, Buffer::Mapped(_)));
+    assert_eq!(input.lines().count(), lines);
+    assert!(input.lines().all(|line| line == "+1"));
+  }
+
+  #[test]
+  fn large_input_has_to_be_utf8() {
+    let path = std::env::temp_dir().join("aoc-large-invalid-input.txt");
+    let mut content = vec![b'+'; MMAP_THRESHOLD as usize];
+    content.push(0xff);
+    fs::write(&path, content).unwrap();
+
+    assert!(matches!(read_input(&path.to_string_lossy()), Err(Error::InvalidInput(_))));
+  }
+
+  #[test]
+  fn hash_bytes_matches_fnv1a() {
+    assert_eq!(hash_bytes(b""), "cbf29ce484222325");
+    assert_eq!(hash_bytes(b"a"), "af63dc4c8601ec8c");
+  }
+}
@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use serde::Deserialize;
+
+use crate::error::{Error, Result};
+use crate::registry;
+use crate::solver::{Answer, Context, Params};
+use crate::utils::read_toml;
+
+pub const DEFAULT_ANSWERS_PATH: &str = "answers.toml";
+
+/// Confirmed answers for a single input of a day, along with the parameters
+/// the solver needs for that input
+#[derive(Debug, Deserialize)]
+pub struct Expected {
+  pub input: String,
+  pub part1: Option<Answer>,
+  pub part2: Option<Answer>,
+  #[serde(default)]
+  pub params: Params
+}
+
+impl Expected {
+  pub fn part(&self, part: u8) -> Option<&Answer> {
+    match part {
+      1 => self.part1.as_ref(),
+      2 => self.part2.as_ref(),
+      _ => None
+    }
+  }
+}
+
+/// Contents of the answers file, a list of confirmed answers for every `[[dayN]]`
+pub struct Answers {
+  days: BTreeMap<u8, Vec<Expected>>
+}
+
+impl Answers {
+  pub fn load(path: &str) -> Result<Answers> {
+    let tables: BTreeMap<String, Vec<Expected>> = read_toml(path)?;
+    let mut days = BTreeMap::new();
+
+    for (key, expected) in tables {
+      let day = key.strip_prefix("day")
+        .and_then(|d| d.parse::<u8>().ok())
+        .ok_or_else(|| Error::InvalidInput(format!("Unexpected table [{}] in {}, expected [[dayN]]", key, path)))?;
+      days.insert(day, expected);
+    }
+
+    Ok(Answers { days })
+  }
+
+  pub fn for_day(&self, day: u8) -> &[Expected] {
+    self.days.get(&day).map_or(&[], |e| e.as_slice())
+  }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+  Pass,
+  Fail { expected: Answer, actual: Answer },
+  Error(String),
+  /// There is no confirmed answer to check against
+  Missing
+}
+
+#[derive(Debug)]
+pub struct Check {
+  pub day: u8,
+  pub part: u8,
+  pub input: String,
+  pub outcome: Outcome
+}
+
+impl Check {
+  pub fn is_failure(&self) -> bool {
+    match self.outcome {
+      Outcome::Fail { .. } | Outcome::Error(_) => true,
+      Outcome::Pass | Outcome::Missing => false
+    }
+  }
+}
+
+/// Checks every part with a confirmed answer for a single input
+pub fn verify_input(day: &registry::Day, expected: &Expected) -> Vec<Check> {
+  let parsed = day.load(&expected.input);
+  let ctx = Context::with_params(expected.params.clone());
+
+  (1 ..= 2).filter_map(|part| expected.part(part).map(|answer| (part, answer)))
+    .map(|(part, answer)| {
+      let outcome = match parsed.as_ref().map(|input| day.solver.solve(part, &**input, &ctx)) {
+        Err(e) => Outcome::Error(e.to_string()),
+        Ok(Err(e)) => Outcome::Error(e.to_string()),
+        Ok(Ok(ref actual)) if actual == answer => Outcome::Pass,
+        Ok(Ok(actual)) => Outcome::Fail { expected: answer.clone(), actual }
+      };
+
+      Check { day: day.day, part, input: expected.input.clone(), outcome }
+    })
+    .collect()
+}
+
+/// Runs every registered solver against all of its confirmed answers. Days without
+/// any confirmed answers are reported as missing
+pub fn verify(answers: &Answers) -> Vec<Check> {
+  registry::days().iter().flat_map(|day| {
+    let expected = answers.for_day(day.day);
+
+    if expected.is_empty() {
+      (1 ..= 2).map(|part| Check {
+        day: day.day, part, input: day.default_input(), outcome: Outcome::Missing
+      }).collect()
+    } else {
+      expected.iter().flat_map(|e| verify_input(day, e)).collect::<Vec<Check>>()
+    }
+  }).collect()
+}
+
+impl fmt::Display for Outcome {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Outcome::Pass => write!(f, "pass"),
+      Outcome::Fail { expected, actual } => write!(f, "FAIL expected {}, got {}", expected, actual),
+      Outcome::Error(e) => write!(f, "ERROR {}", e),
+      Outcome::Missing => write!(f, "missing")
+    }
+  }
+}
+
+/// Renders results of the checks as a plain text table
+pub fn render_table(checks: &[Check]) -> String {
+  let input_width = checks.iter().map(|c| c.input.len()).max().unwrap_or(0).max("Input".len());
+  let mut table = format!("Day  Part  {:width$}  Result\n", "Input", width = input_width);
+
+  for check in checks {
+    table += &format!("{:>3}  {:>4}  {:width$}  {}\n", check.day, check.part, check.input, check.outcome, width = input_width);
+  }
+
+  let failed = checks.iter().filter(|c| c.is_failure()).count();
+  let passed = checks.iter().filter(|c| c.outcome == Outcome::Pass).count();
+  table += &format!("\n{} passed, {} failed, {} missing\n", passed, failed, checks.len() - passed - failed);
+
+  table
+}
@@ -0,0 +1,143 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::config::Config;
+use crate::examples;
+use crate::registry::Day;
+use crate::runner::{self, Status};
+
+pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Modification time and size of every watched file, a change in either triggers a new run
+pub type Snapshot = BTreeMap<PathBuf, (Option<SystemTime>, u64)>;
+
+/// Files in the watched directories, not recursive, and the watched files themselves
+pub fn snapshot(paths: &[PathBuf]) -> Snapshot {
+  let mut files: Vec<PathBuf> = Vec::new();
+
+  for path in paths {
+    if path.is_dir() {
+      if let Ok(entries) = fs::read_dir(path) {
+        files.extend(entries.flatten().map(|e| e.path()).filter(|p| p.is_file()));
+      }
+    } else {
+      files.push(path.clone());
+    }
+  }
+
+  files.into_iter()
+    .filter_map(|file| fs::metadata(&file).ok().map(|m| (file, (m.modified().ok(), m.len()))))
+    .collect()
+}
+
+/// Result of one part against one input, keyed by the input and the part
+pub type Round = BTreeMap<(String, u8), String>;
+
+/// Solves both parts against the day input, then checks all the examples of the day
+pub fn run_round(day: &Day, config: &Config) -> Round {
+  let mut round = Round::new();
+  let ctx = config.context(day.day);
+
+  for result in runner::run_day(day, &config.input(day.day), &[1, 2], &ctx) {
+    let outcome = match result.status {
+      Status::Solved(answer) => answer.to_string(),
+      Status::Failed(e) => format!("ERROR {}", e),
+      Status::TimedOut(budget) => format!("TIMEOUT after {:?}", budget)
+    };
+    round.insert((result.input, result.part), outcome);
+  }
+
+  match examples::discover_day(&config.data_dir, day.day) {
+    Ok(found) => for check in found.iter().flat_map(examples::run) {
+      round.insert((check.input, check.part), check.outcome.to_string());
+    },
+    Err(e) => {
+      round.insert((config.day_dir(day.day), 0), format!("ERROR {}", e));
+    }
+  }
+
+  round
+}
+
+fn label(input: &str, part: u8) -> String {
+  let name = Path::new(input).file_name().map_or_else(|| input.to_string(), |n| n.to_string_lossy().to_string());
+
+  if part == 0 { name } else { format!("{} part{}", name, part) }
+}
+
+/// Compact diff of two rounds: changed, added and removed results, and a count of unchanged ones
+pub fn diff(previous: &Round, current: &Round) -> String {
+  let mut lines: Vec<String> = Vec::new();
+  let mut unchanged = 0;
+
+  for ((input, part), outcome) in current {
+    match previous.get(&(input.clone(), *part)) {
+      Some(before) if before == outcome => unchanged += 1,
+      Some(before) => lines.push(format!("~ {}: {} -> {}", label(input, *part), before, outcome)),
+      None => lines.push(format!("+ {}: {}", label(input, *part), outcome))
+    }
+  }
+  for ((input, part), outcome) in previous {
+    if !current.contains_key(&(input.clone(), *part)) {
+      lines.push(format!("- {}: {}", label(input, *part), outcome));
+    }
+  }
+
+  lines.push(format!("({} unchanged)", unchanged));
+  lines.join("\n") + "\n"
+}
+
+/// Files that trigger a new run: the data directory of the day and its input, which
+/// may live somewhere else
+pub fn watched_paths(day: &Day, config: &Config) -> Vec<PathBuf> {
+  vec![PathBuf::from(config.day_dir(day.day)), PathBuf::from(config.input(day.day))]
+}
+
+/// Runs the day, then polls its files and runs it again on every change, printing what changed.
+/// Only returns after `rounds` runs, when given
+pub fn watch(day: &Day, config: &Config, interval: Duration, rounds: Option<usize>) {
+  let paths = watched_paths(day, config);
+  let mut files = snapshot(&paths);
+  let mut previous = run_round(day, config);
+  print!("{}", diff(&Round::new(), &previous));
+
+  let mut count = 1;
+  while rounds.is_none_or(|r| count < r) {
+    thread::sleep(interval);
+
+    let current_files = snapshot(&paths);
+    if current_files == files {
+      continue;
+    }
+
+    files = current_files;
+    let current = run_round(day, config);
+    println!("--- Day{} changed", day.day);
+    print!("{}", diff(&previous, &current));
+    previous = current;
+    count += 1;
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn round(results: &[(&str, u8, &str)]) -> Round {
+    results.iter().map(|(input, part, outcome)| ((input.to_string(), *part), outcome.to_string())).collect()
+  }
+
+  #[test]
+  fn diff_shows_only_changes() {
+    let previous = round(&[("data/day1/input.txt", 1, "556"), ("data/day1/input.txt", 2, "448"), ("data/day1/a.txt", 1, "pass")]);
+    let current = round(&[("data/day1/input.txt", 1, "556"), ("data/day1/input.txt", 2, "449"), ("data/day1/b.txt", 1, "pass")]);
+
+    assert_eq!(
+      diff(&previous, &current),
+      "+ b.txt part1: pass\n~ input.txt part2: 448 -> 449\n- a.txt part1: pass\n(1 unchanged)\n"
+    );
+  }
+}
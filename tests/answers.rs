@@ -0,0 +1,16 @@
+extern crate rust_advent_of_code_2018;
+
+use rust_advent_of_code_2018::registry;
+use rust_advent_of_code_2018::verify::{verify, render_table, Answers, Outcome, DEFAULT_ANSWERS_PATH};
+
+#[test]
+fn all_solvers_match_confirmed_answers() {
+  let answers = Answers::load(DEFAULT_ANSWERS_PATH).expect("Unable to load answers");
+  let checks = verify(&answers);
+
+  // Builds without any day have nothing to pass
+  if !registry::days().is_empty() {
+    assert!(checks.iter().any(|c| c.outcome == Outcome::Pass));
+  }
+  assert!(!checks.iter().any(|c| c.is_failure()), "\n{}", render_table(&checks));
+}
@@ -0,0 +1,119 @@
+extern crate rust_advent_of_code_2018;
+
+use rust_advent_of_code_2018::registry;
+use rust_advent_of_code_2018::utils::read_input;
+
+#[test]
+fn registry_lists_days_in_order() {
+  let days: Vec<u8> = registry::days().iter().map(|d| d.day).collect();
+  let mut sorted = days.clone();
+  sorted.sort();
+
+  assert_eq!(days, sorted);
+  assert!(days.iter().all(|day| registry::find(*day).is_some()));
+}
+
+#[cfg(feature = "day7")]
+#[test]
+fn registered_solver_runs_example_input() {
+  use rust_advent_of_code_2018::solver::{Answer, Context};
+
+  let day = registry::find(7).expect("Day 7 is not registered");
+  let raw = read_input("data/day7/input_example.txt").expect("Unable to read example");
+  let input = day.solver.parse_input(&raw).expect("Unable to parse example");
+
+  assert_eq!(day.solver.solve(1, &*input, &Context::default()).expect("Unable to solve example"), Answer::Text("CABDFE".to_string()));
+}
+
+#[cfg(feature = "day3")]
+#[test]
+fn claims_overlap_only_when_sharing_area() {
+  use rust_advent_of_code_2018::day3::Claim;
+
+  let c1 = Claim::from_input("#1 @ 1,3: 4x4").expect("Unable to parse claim");
+  let c2 = Claim::from_input("#2 @ 3,1: 4x4").expect("Unable to parse claim");
+  let c3 = Claim::from_input("#3 @ 5,5: 2x2").expect("Unable to parse claim");
+
+  assert!(c1.overlaps_with(&c2));
+  assert!(!c1.overlaps_with(&c3));
+  assert_eq!(c3.area().map(|area| area.size()), Some(4));
+}
+
+#[cfg(feature = "day4")]
+#[test]
+fn guards_log_tracks_minutes_asleep() {
+  use rust_advent_of_code_2018::day4::Day4;
+  use rust_advent_of_code_2018::solver::Solver;
+  use rust_advent_of_code_2018::trace::Trace;
+  use rust_advent_of_code_2018::utils::RawInput;
+
+  let input = RawInput::from_text(
+    "[1518-11-01 00:05] falls asleep\n[1518-11-01 00:00] Guard #10 begins shift\n[1518-11-01 00:25] wakes up\n"
+  );
+  let log = Day4.parse(&input).expect("Unable to parse log").guards_log(&Trace::default()).expect("Unable to replay log");
+  let guard = &log.guards()[&10];
+
+  assert_eq!(guard.minutes_asleep().iter().sum::<i32>(), 20);
+  assert_eq!(guard.minutes_asleep()[5], 1);
+  assert_eq!(guard.minutes_asleep()[25], 0);
+}
+
+#[cfg(feature = "day7")]
+#[test]
+fn steps_are_resolved_in_order_and_time() {
+  use rust_advent_of_code_2018::cancel::Cancellation;
+  use rust_advent_of_code_2018::day7::{build_graph, parse_dependency, resolve_order, resolve_time};
+  use rust_advent_of_code_2018::trace::Trace;
+
+  let dependencies: Vec<(u8, u8)> = read_input("data/day7/input_example.txt").expect("Unable to read example")
+    .lines()
+    .map(|line| parse_dependency(line).expect("Unable to parse dependency"))
+    .collect();
+
+  assert_eq!(resolve_order(&mut build_graph(&dependencies), &Cancellation::new()).expect("Unable to resolve order"), "CABDFE");
+  assert_eq!(
+    resolve_time(&mut build_graph(&dependencies), 0, 2, &Trace::default(), &Cancellation::new()).expect("Unable to resolve time"), 15
+  );
+}
+
+#[cfg(feature = "day8")]
+#[test]
+fn tree_calculates_metadata_sum_and_value() {
+  use rust_advent_of_code_2018::day8::Tree;
+
+  let tree = Tree::parse("2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2").expect("Unable to parse tree");
+
+  assert_eq!(tree.metadata_sum().expect("Unable to sum metadata"), 138);
+  assert_eq!(tree.root_value().expect("Unable to calculate value"), 66);
+}
+
+#[cfg(feature = "day9")]
+#[test]
+fn marble_game_calculates_high_score() {
+  use rust_advent_of_code_2018::day9::Game;
+
+  assert_eq!(Game::new(9, 25).high_score().expect("Unable to play"), 32);
+  assert_eq!(Game::new(10, 1618).high_score().expect("Unable to play"), 8317);
+  assert!(Game::new(0, 25).high_score().is_err());
+}
+
+#[cfg(feature = "day1")]
+#[test]
+fn parse_errors_point_at_the_input_line() {
+  use rust_advent_of_code_2018::day1::Day1;
+  use rust_advent_of_code_2018::error::Error;
+  use rust_advent_of_code_2018::solver::Solver;
+  use rust_advent_of_code_2018::utils::RawInput;
+
+  match Day1.parse(&RawInput::from_text("+1\r\n-2\r\n+x\r\n")) {
+    Err(Error::Parse { line, .. }) => assert_eq!(line, 3),
+    _ => panic!("Expected a parse error")
+  }
+}
+
+#[test]
+fn missing_input_is_reported_as_io_error() {
+  use rust_advent_of_code_2018::error::Error;
+
+  assert!(matches!(read_input("data/missing/input.txt"), Err(Error::Io { .. })));
+}
@@ -0,0 +1,65 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread::{self, JoinHandle};
+
+/// Request as seen by the mock server
+#[derive(Debug, PartialEq)]
+pub struct Request {
+  pub line: String,
+  pub cookie: String,
+  pub body: String
+}
+
+/// Stand-in for the puzzle server, answers the given responses in order and
+/// returns the requests it received. Base URL of the server is returned with the handle
+pub fn serve(responses: Vec<(u16, &'static str)>) -> (String, JoinHandle<Vec<Request>>) {
+  let listener = TcpListener::bind("127.0.0.1:0").expect("Unable to bind mock server");
+  let base_url = format!("http://{}/2018", listener.local_addr().unwrap());
+
+  let handle = thread::spawn(move || {
+    responses.into_iter().map(|(status, body)| {
+      let (mut stream, _) = listener.accept().expect("Unable to accept connection");
+      let mut reader = BufReader::new(stream.try_clone().unwrap());
+      let mut line = String::new();
+      let mut cookie = String::new();
+      let mut length = 0;
+      reader.read_line(&mut line).unwrap();
+
+      loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).unwrap();
+        if header.trim().is_empty() {
+          break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+          match name.to_ascii_lowercase().as_str() {
+            "cookie" => cookie = value.trim().to_string(),
+            "content-length" => length = value.trim().parse().unwrap(),
+            _ => {}
+          }
+        }
+      }
+
+      let mut request_body = vec![0; length];
+      reader.read_exact(&mut request_body).unwrap();
+      write!(
+        stream, "HTTP/1.1 {} Mock\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", status, body.len(), body
+      ).unwrap();
+
+      Request { line: line.trim().to_string(), cookie, body: String::from_utf8(request_body).unwrap() }
+    }).collect()
+  });
+
+  (base_url, handle)
+}
+
+/// Default config pointed at the server, with a fresh data directory
+pub fn config(name: &str, base_url: String) -> rust_advent_of_code_2018::config::Config {
+  let data_dir = std::env::temp_dir().join(format!("aoc-{}-{}", name, std::process::id()));
+  let _ = std::fs::remove_dir_all(&data_dir);
+
+  let mut config = rust_advent_of_code_2018::config::Config::default();
+  config.data_dir = data_dir.to_string_lossy().to_string();
+  config.base_url = base_url;
+  config
+}
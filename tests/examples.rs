@@ -0,0 +1,17 @@
+extern crate rust_advent_of_code_2018;
+
+use rust_advent_of_code_2018::examples::{discover, run, DEFAULT_DATA_DIR};
+use rust_advent_of_code_2018::registry;
+use rust_advent_of_code_2018::verify::render_table;
+
+#[test]
+fn all_examples_match_expected_answers() {
+  let examples = discover(DEFAULT_DATA_DIR).expect("Unable to discover examples");
+  let checks: Vec<_> = examples.iter().flat_map(run).collect();
+
+  // Builds without any day have no examples to run
+  if !registry::days().is_empty() {
+    assert!(!checks.is_empty());
+  }
+  assert!(!checks.iter().any(|c| c.is_failure()), "\n{}", render_table(&checks));
+}
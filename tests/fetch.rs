@@ -0,0 +1,51 @@
+extern crate rust_advent_of_code_2018;
+
+mod common;
+
+use std::fs;
+
+use common::{config, serve, Request};
+use rust_advent_of_code_2018::error::Error;
+use rust_advent_of_code_2018::fetch::{fetch, Fetched};
+
+#[test]
+fn input_is_downloaded_once_and_cached() {
+  let (base_url, server) = serve(vec![(200, "+1\n-2\n")]);
+  let config = config("fetch-cached", base_url);
+
+  match fetch(1, &config, Some("secret")).expect("Unable to fetch input") {
+    Fetched::Downloaded(path, size) => {
+      assert_eq!(fs::read_to_string(&path).unwrap(), "+1\n-2\n");
+      assert_eq!(size, 6);
+    },
+    other => panic!("Expected a download, got {:?}", other)
+  }
+  assert_eq!(fetch(1, &config, Some("secret")).unwrap(), Fetched::Cached(config.input(1)));
+
+  let requests = server.join().unwrap();
+  assert_eq!(requests, vec![Request {
+    line: "GET /2018/day/1/input HTTP/1.1".to_string(),
+    cookie: "session=secret".to_string(),
+    body: String::new()
+  }]);
+}
+
+#[test]
+fn http_errors_are_reported_with_status() {
+  let (base_url, server) = serve(vec![(404, "Please don't repeatedly request this endpoint before it unlocks!")]);
+  let config = config("fetch-missing", base_url);
+
+  match fetch(25, &config, Some("secret")) {
+    Err(e @ Error::Http { status: Some(404), .. }) => assert!(e.to_string().contains("not unlocked yet")),
+    other => panic!("Expected an HTTP error, got {:?}", other)
+  }
+  assert!(!std::path::Path::new(&config.input(25)).exists());
+  server.join().unwrap();
+}
+
+#[test]
+fn missing_session_is_reported_before_any_request() {
+  let config = config("fetch-session", "http://127.0.0.1:9/2018".to_string());
+
+  assert!(matches!(fetch(1, &config, None), Err(Error::Http { status: None, .. })));
+}
@@ -0,0 +1,96 @@
+extern crate rust_advent_of_code_2018;
+
+use std::fs;
+
+use rust_advent_of_code_2018::error::Error;
+use rust_advent_of_code_2018::examples::{discover, DEFAULT_DATA_DIR};
+use rust_advent_of_code_2018::fuzz;
+use rust_advent_of_code_2018::generator::Rng;
+use rust_advent_of_code_2018::registry;
+use rust_advent_of_code_2018::solver::Answer;
+
+/// Checks an error is the one expected
+type Expected = fn(&Error) -> bool;
+
+/// Inputs that used to panic, overflow or never finish, with the error each of them gives
+/// now. Errors come from parsing the input, or from the first part that fails
+const HOSTILE: &[(u8, &str, Expected)] = &[
+  (1, "+1\n+1", |e| matches!(e, Error::NoSolution(_))),
+  (1, "+2147483647\n+2147483647\n+2147483647", |e| matches!(e, Error::NoSolution(_))),
+  (1, "+", |e| matches!(e, Error::Parse { .. })),
+  (3, "#1 @ 2147483647,2147483647: 2147483647x2147483647", |e| matches!(e, Error::InvalidInput(_))),
+  (3, "#99999999999 @ 1,1: 1x1", |e| matches!(e, Error::Parse { .. })),
+  (4, "[1518-11-01 00:05] falls asleep", |e| matches!(e, Error::InvalidInput(_))),
+  (4, "[1518-11-01 00:00] Guard #1 begins shift\n[1518-11-01 00:05] wakes up", |e| matches!(e, Error::InvalidInput(_))),
+  (4, "[1518-11-01 00:00] Guard #99999999999 begins shift", |e| matches!(e, Error::Parse { .. })),
+  (4, "[1518-13-45 25:61] Guard #1 begins shift", |e| matches!(e, Error::Parse { .. })),
+  (5, "\u{e9}", |e| matches!(e, Error::Parse { .. })),
+  (7, "Step A must be finished before step A can begin.", |e| matches!(e, Error::Cycle(_))),
+  (8, "0 2 18446744073709551615 1", |e| matches!(e, Error::InvalidInput(_))),
+  (8, "18446744073709551615 18446744073709551615", |e| matches!(e, Error::InvalidInput(_))),
+  (8, "99999999999999999999999", |e| matches!(e, Error::Parse { .. })),
+  (9, "1 players; last marble is worth 4294967295 points", |e| matches!(e, Error::InvalidInput(_))),
+  (9, "99999999999999 players; last marble is worth 25 points", |e| matches!(e, Error::InvalidInput(_))),
+  (9, "0 players; last marble is worth 0 points", |e| matches!(e, Error::InvalidInput(_)))
+];
+
+/// Inputs close to the limits that still have answers
+const EDGE_CASES: &[(u8, &str, [i64; 2])] = &[
+  (1, "+1000000000\n-999999999", [1, 1_000_000_000]),
+  (5, "aA", [0, 0])
+];
+
+fn first_error(day: u8, input: &str) -> Option<Error> {
+  match fuzz::solve(day, input.as_bytes()) {
+    Err(e) => Some(e),
+    Ok(parts) => parts.into_iter().find_map(Result::err)
+  }
+}
+
+#[test]
+fn hostile_inputs_give_errors() {
+  for (day, input, expected) in HOSTILE.iter().filter(|(day, _, _)| registry::find(*day).is_some()) {
+    match first_error(*day, input) {
+      Some(e) => assert!(expected(&e), "Unexpected error of day {} for {:?}: {:?}", day, input, e),
+      None => panic!("Day {} solved hostile input {:?}", day, input)
+    }
+  }
+
+  for (day, input, answers) in EDGE_CASES.iter().filter(|(day, _, _)| registry::find(*day).is_some()) {
+    let solved: Vec<Answer> = fuzz::solve(*day, input.as_bytes()).expect("Unable to parse edge case")
+      .into_iter()
+      .collect::<Result<_, _>>()
+      .expect("Unable to solve edge case");
+    assert_eq!(solved, answers.iter().map(|a| Answer::Number(*a)).collect::<Vec<Answer>>());
+  }
+
+  let deep: String = "1 1 ".repeat(100_000) + "0 1" + &" 1".repeat(100_000);
+  if registry::find(8).is_some() {
+    let results = fuzz::solve(8, deep.as_bytes()).expect("Deep tree doesn't parse");
+    assert!(results.iter().all(Result::is_err));
+  }
+  assert!(fuzz::parse(1, &[0xff, 0xfe]).is_err());
+}
+
+#[test]
+fn mutated_examples_never_panic() {
+  let mut rng = Rng::new(2018);
+
+  for example in discover(DEFAULT_DATA_DIR).expect("Unable to discover examples") {
+    let original = fs::read(&example.expected.input).expect("Unable to read example");
+
+    for _ in 0 .. 100 {
+      let mut data = original.clone();
+      for _ in 0 .. 1 + rng.below(3) {
+        let at = rng.below(data.len().max(1));
+        match rng.below(3) {
+          0 if !data.is_empty() => data[at] = original[rng.below(original.len())],
+          1 if !data.is_empty() => { data.remove(at); },
+          _ => data.insert(at.min(data.len()), rng.next_u64() as u8)
+        }
+      }
+
+      let _ = fuzz::solve(example.day, &data);
+    }
+  }
+}
@@ -0,0 +1,28 @@
+extern crate rust_advent_of_code_2018;
+
+use rust_advent_of_code_2018::generator::{generate, GENERATED_DAYS};
+use rust_advent_of_code_2018::registry;
+use rust_advent_of_code_2018::solver::Context;
+use rust_advent_of_code_2018::utils::RawInput;
+
+#[test]
+fn generated_inputs_are_solved_with_known_answers() {
+  let ctx = Context::default();
+
+  for day in registry::days().into_iter().filter(|d| GENERATED_DAYS.contains(&d.day)) {
+    for seed in 0 .. 5 {
+      let generated = generate(day.day, seed, 24).expect("Unable to generate input");
+      let input = day.solver.parse_input(&RawInput::from_text(&generated.input))
+        .unwrap_or_else(|e| panic!("Day{} input of seed {} doesn't parse: {}", day.day, seed, e));
+
+      for (part, expected) in [(1, &generated.part1), (2, &generated.part2)] {
+        let answer = day.solver.solve(part, &*input, &ctx)
+          .unwrap_or_else(|e| panic!("Day{}, part{} of seed {} failed: {}", day.day, part, seed, e));
+
+        if let Some(expected) = expected {
+          assert_eq!(&answer, expected, "Day{}, part{} of seed {}", day.day, part, seed);
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,33 @@
+#![cfg(unix)]
+extern crate rust_advent_of_code_2018;
+
+use std::path::Path;
+
+use rust_advent_of_code_2018::isolate::{run_isolated, Limits};
+use rust_advent_of_code_2018::registry;
+use rust_advent_of_code_2018::runner::{Job, Status};
+use rust_advent_of_code_2018::solver::{Context, Params};
+
+#[test]
+fn parts_over_limits_fail_without_stopping_the_others() {
+  let day = match registry::find(9) {
+    Some(day) => day,
+    None => return
+  };
+  let mut params = Params::new();
+  params.set("last_marble", 200_000);
+  let jobs = vec![
+    Job { day, part: 2, input: "data/day9/example1.txt".to_string(), ctx: Context::with_params(params) },
+    Job { day, part: 1, input: "data/day9/example1.txt".to_string(), ctx: Context::default() }
+  ];
+
+  let program = Path::new(env!("CARGO_BIN_EXE_rust-advent-of-code-2018"));
+  let results = run_isolated(&jobs, 2, program, Limits { cpu_seconds: 10, memory_mib: 64 });
+
+  match &results[0].status {
+    Status::Failed(message) => assert!(message.contains("memory allocation"), "Unexpected failure: {}", message),
+    other => panic!("Part over the memory limit didn't fail: {:?}", other)
+  }
+  assert_eq!(results[1].status, Status::Solved(32.into()));
+  assert!(results[1].input_hash.is_some());
+}
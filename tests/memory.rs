@@ -0,0 +1,25 @@
+//! Needs the counting allocator installed, run with `cargo test --features memory`
+#![cfg(feature = "memory")]
+extern crate rust_advent_of_code_2018;
+
+use rust_advent_of_code_2018::memory;
+use rust_advent_of_code_2018::registry;
+use rust_advent_of_code_2018::runner::{run_day, Status};
+use rust_advent_of_code_2018::solver::{Answer, Context};
+
+/// Numeric answers don't need any memory after the part is solved, anything still live
+/// is a leak, like the reference cycles day 9 used to leave behind
+#[test]
+fn parts_with_numeric_answers_leave_nothing_live() {
+  memory::track(true).expect("Counting allocator is not installed");
+
+  for day in registry::days() {
+    for result in run_day(&day, &day.default_input(), &[1, 2], &Context::default()) {
+      let stats = result.memory.expect("Memory was not measured");
+
+      if let Status::Solved(Answer::Number(_)) = result.status {
+        assert_eq!(stats.live_bytes, 0, "Day{}, part{} leaks: {}", result.day, result.part, stats);
+      }
+    }
+  }
+}
@@ -0,0 +1,379 @@
+extern crate quickcheck;
+extern crate rust_advent_of_code_2018;
+
+// Properties that have to hold for any input of a day, checked on inputs from generators
+// of each day's domain. Failing inputs are shrunk before they are reported
+
+#[cfg(any(
+  feature = "day1", feature = "day2", feature = "day3", feature = "day4",
+  feature = "day5", feature = "day7", feature = "day8", feature = "day9"
+))]
+use quickcheck::{Arbitrary, Gen};
+
+#[cfg(any(
+  feature = "day1", feature = "day2", feature = "day3", feature = "day4",
+  feature = "day5", feature = "day7", feature = "day8", feature = "day9"
+))]
+fn below(g: &mut Gen, n: usize) -> usize {
+  usize::arbitrary(g) % n
+}
+
+#[cfg(feature = "day1")]
+mod day1 {
+  use std::collections::HashSet;
+  use super::*;
+  use quickcheck::QuickCheck;
+  use rust_advent_of_code_2018::day1::Day1;
+  use rust_advent_of_code_2018::solver::{Answer, Context, Puzzle};
+  use rust_advent_of_code_2018::utils::RawInput;
+
+  /// Few small changes, so the first repeat is reached within a few hundred passes
+  #[derive(Debug, Clone)]
+  struct Changes(Vec<i32>);
+
+  impl Arbitrary for Changes {
+    fn arbitrary(g: &mut Gen) -> Changes {
+      Changes((0 .. 1 + below(g, 10)).map(|_| below(g, 41) as i32 - 20).collect())
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Changes>> {
+      Box::new(self.0.shrink().filter(|c| !c.is_empty()).map(Changes))
+    }
+  }
+
+  fn simulated_repeat(changes: &[i32], passes: usize) -> Option<i64> {
+    let mut seen: HashSet<i64> = std::iter::once(0).collect();
+    let mut frequency = 0_i64;
+
+    changes.iter().cycle().take(changes.len() * passes)
+      .find_map(|c| {
+        frequency += i64::from(*c);
+        if seen.insert(frequency) { None } else { Some(frequency) }
+      })
+  }
+
+  #[test]
+  fn first_repeat_matches_simulation() {
+    fn property(changes: Changes) -> bool {
+      let text: Vec<String> = changes.0.iter().map(|c| format!("{:+}", c)).collect();
+      let input = Day1.parse_input(&RawInput::from_text(&text.join("\n"))).unwrap();
+      let solved = Day1.solve(2, &*input, &Context::default()).ok();
+
+      solved == simulated_repeat(&changes.0, 1000).map(Answer::Number)
+    }
+
+    QuickCheck::new().quickcheck(property as fn(Changes) -> bool);
+  }
+}
+
+#[cfg(feature = "day2")]
+mod day2 {
+  use super::*;
+  use quickcheck::QuickCheck;
+  use rust_advent_of_code_2018::day2::Day2;
+  use rust_advent_of_code_2018::solver::{Answer, Context, Puzzle};
+  use rust_advent_of_code_2018::utils::RawInput;
+
+  /// Two ids differing in a single position, hidden among ids of other lengths
+  /// that can't be close to them
+  #[derive(Debug, Clone)]
+  struct BoxIds { ids: Vec<String>, common: String }
+
+  impl Arbitrary for BoxIds {
+    fn arbitrary(g: &mut Gen) -> BoxIds {
+      let letters: Vec<char> = ('a' ..= 'z').collect();
+      let id = |g: &mut Gen, length: usize| -> Vec<char> {
+        (0 .. length).map(|_| *g.choose(&letters).unwrap()).collect()
+      };
+
+      let length = 1 + below(g, 10);
+      let first = id(g, length);
+      let position = below(g, length);
+      let mut second = first.clone();
+      second[position] = if first[position] == 'z' { 'a' } else { char::from(first[position] as u8 + 1) };
+
+      let mut ids: Vec<String> = (0 .. below(g, 10)).map(|i| id(g, length + 1 + i).into_iter().collect()).collect();
+      ids.insert(below(g, ids.len() + 1), first.iter().collect());
+      ids.insert(below(g, ids.len() + 1), second.iter().collect());
+
+      let common = first.iter().enumerate().filter(|(i, _)| *i != position).map(|(_, c)| *c).collect();
+      BoxIds { ids, common }
+    }
+  }
+
+  #[test]
+  fn close_ids_are_found_in_any_order() {
+    fn property(box_ids: BoxIds) -> bool {
+      let input = Day2.parse_input(&RawInput::from_text(&box_ids.ids.join("\n"))).unwrap();
+
+      Day2.solve(2, &*input, &Context::default()).ok() == Some(Answer::Text(box_ids.common))
+    }
+
+    QuickCheck::new().quickcheck(property as fn(BoxIds) -> bool);
+  }
+}
+
+#[cfg(feature = "day3")]
+mod day3 {
+  use std::collections::HashSet;
+  use super::*;
+  use quickcheck::QuickCheck;
+  use rust_advent_of_code_2018::day3::Claim;
+
+  #[derive(Debug, Clone)]
+  struct Rect { x: i32, y: i32, width: i32, height: i32 }
+
+  impl Rect {
+    fn claim(&self, id: i32) -> Claim {
+      Claim { id, x: self.x, y: self.y, width: self.width, height: self.height }
+    }
+  }
+
+  /// Small claims on a small piece of fabric, so that about half of them overlap
+  impl Arbitrary for Rect {
+    fn arbitrary(g: &mut Gen) -> Rect {
+      Rect { x: below(g, 10) as i32, y: below(g, 10) as i32, width: below(g, 6) as i32, height: below(g, 6) as i32 }
+    }
+  }
+
+  #[test]
+  fn overlap_is_symmetric_and_means_shared_points() {
+    fn property(a: Rect, b: Rect) -> bool {
+      let (a, b) = (a.claim(1), b.claim(2));
+      let points = |claim: &Claim| claim.area().map_or_else(HashSet::new, |area| area.points().collect::<HashSet<_>>());
+      let shares_points = !points(&a).is_disjoint(&points(&b));
+
+      a.overlaps_with(&b) == b.overlaps_with(&a) && a.overlaps_with(&b) == shares_points
+    }
+
+    QuickCheck::new().quickcheck(property as fn(Rect, Rect) -> bool);
+  }
+}
+
+#[cfg(feature = "day4")]
+mod day4 {
+  use super::*;
+  use quickcheck::QuickCheck;
+  use rust_advent_of_code_2018::day4::Day4;
+  use rust_advent_of_code_2018::generator::generate;
+  use rust_advent_of_code_2018::solver::{Context, Puzzle};
+  use rust_advent_of_code_2018::utils::RawInput;
+
+  /// Log of the input generator, in the order it was generated and shuffled again
+  #[derive(Debug, Clone)]
+  struct GuardLog { lines: Vec<String>, shuffled: Vec<String> }
+
+  impl Arbitrary for GuardLog {
+    fn arbitrary(g: &mut Gen) -> GuardLog {
+      let generated = generate(4, u64::arbitrary(g), 1 + below(g, 30)).unwrap();
+      let lines: Vec<String> = generated.input.lines().map(String::from).collect();
+      let mut shuffled = lines.clone();
+      for i in (1 .. shuffled.len()).rev() {
+        shuffled.swap(i, below(g, i + 1));
+      }
+
+      GuardLog { lines, shuffled }
+    }
+  }
+
+  #[test]
+  fn answers_do_not_depend_on_order_of_lines() {
+    fn property(log: GuardLog) -> bool {
+      let solve = |lines: &[String]| {
+        let input = Day4.parse_input(&RawInput::from_text(&lines.join("\n"))).unwrap();
+        [1, 2].iter().map(|part| Day4.solve(*part, &*input, &Context::default()).ok()).collect::<Vec<_>>()
+      };
+
+      solve(&log.lines) == solve(&log.shuffled)
+    }
+
+    QuickCheck::new().quickcheck(property as fn(GuardLog) -> bool);
+  }
+}
+
+#[cfg(feature = "day5")]
+mod day5 {
+  use super::*;
+  use quickcheck::QuickCheck;
+  use rust_advent_of_code_2018::day5::reduce_polymer;
+
+  /// Units of a few types only, so that plenty of them react
+  #[derive(Debug, Clone)]
+  struct Polymer(String);
+
+  impl Arbitrary for Polymer {
+    fn arbitrary(g: &mut Gen) -> Polymer {
+      let units = ['a', 'A', 'b', 'B', 'c', 'C'];
+      let length = below(g, g.size());
+      Polymer((0 .. length).map(|_| *g.choose(&units).unwrap()).collect())
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Polymer>> {
+      let units: Vec<char> = self.0.chars().collect();
+      Box::new(units.shrink().map(|u| Polymer(u.into_iter().collect())))
+    }
+  }
+
+  #[test]
+  fn reduction_is_idempotent() {
+    fn property(polymer: Polymer) -> bool {
+      let reduced = reduce_polymer(&polymer.0);
+      let reacting = reduced.as_bytes().windows(2).any(|w| w[0] != w[1] && w[0].eq_ignore_ascii_case(&w[1]));
+
+      reduce_polymer(&reduced) == reduced && !reacting
+    }
+
+    QuickCheck::new().quickcheck(property as fn(Polymer) -> bool);
+  }
+}
+
+#[cfg(feature = "day7")]
+mod day7 {
+  use super::*;
+  use quickcheck::QuickCheck;
+  use rust_advent_of_code_2018::cancel::Cancellation;
+  use rust_advent_of_code_2018::day7::{build_graph, resolve_order};
+
+  /// Steps `1 ..= 26` in a random order, every edge goes from an earlier step to
+  /// a later one, so the graph has no cycles. Removing edges keeps it that way
+  #[derive(Debug, Clone)]
+  struct Dependencies(Vec<(u8, u8)>);
+
+  impl Arbitrary for Dependencies {
+    fn arbitrary(g: &mut Gen) -> Dependencies {
+      let mut steps: Vec<u8> = (1 ..= 26).collect();
+      for i in (1 .. steps.len()).rev() {
+        steps.swap(i, below(g, i + 1));
+      }
+      let steps = &steps[.. 2 + below(g, 25)];
+
+      Dependencies((0 .. below(g, 40)).filter_map(|_| {
+        let (a, b) = (below(g, steps.len()), below(g, steps.len()));
+        if a < b { Some((steps[a], steps[b])) } else { None }
+      }).collect())
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Dependencies>> {
+      Box::new(self.0.shrink().map(Dependencies))
+    }
+  }
+
+  #[test]
+  fn order_respects_every_dependency() {
+    fn property(dependencies: Dependencies) -> bool {
+      let order = resolve_order(&mut build_graph(&dependencies.0), &Cancellation::new()).unwrap();
+      let position = |step: u8| order.find(char::from(step + b'A' - 1));
+      let mut steps: Vec<u8> = dependencies.0.iter().flat_map(|(a, b)| vec![*a, *b]).collect();
+      steps.sort_unstable();
+      steps.dedup();
+
+      order.len() == steps.len() &&
+        dependencies.0.iter().all(|(before, after)| position(*before) < position(*after))
+    }
+
+    QuickCheck::new().quickcheck(property as fn(Dependencies) -> bool);
+  }
+}
+
+#[cfg(feature = "day8")]
+mod day8 {
+  use super::*;
+  use quickcheck::QuickCheck;
+  use rust_advent_of_code_2018::day8::Tree;
+
+  #[derive(Debug, Clone)]
+  struct Node {
+    children: Vec<Node>,
+    metadata: Vec<usize>
+  }
+
+  impl Node {
+    fn generate(g: &mut Gen, depth: usize) -> Node {
+      let children = if depth < 4 { below(g, 4) } else { 0 };
+
+      Node {
+        children: (0 .. children).map(|_| Node::generate(g, depth + 1)).collect(),
+        metadata: (0 .. 1 + below(g, 3)).map(|_| below(g, 10)).collect()
+      }
+    }
+
+    fn serialize(&self, tree: &mut Vec<usize>) {
+      tree.push(self.children.len());
+      tree.push(self.metadata.len());
+      self.children.iter().for_each(|c| c.serialize(tree));
+      tree.extend_from_slice(&self.metadata);
+    }
+
+    fn metadata_sum(&self) -> usize {
+      self.metadata.iter().sum::<usize>() + self.children.iter().map(Node::metadata_sum).sum::<usize>()
+    }
+
+    /// Metadata entries of a node with children pick them by their 1-based index
+    fn value(&self) -> usize {
+      if self.children.is_empty() {
+        return self.metadata.iter().sum();
+      }
+
+      self.metadata.iter()
+        .filter_map(|m| m.checked_sub(1).and_then(|i| self.children.get(i)))
+        .map(Node::value)
+        .sum()
+    }
+  }
+
+  impl Arbitrary for Node {
+    fn arbitrary(g: &mut Gen) -> Node {
+      Node::generate(g, 0)
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Node>> {
+      Box::new(self.children.clone().into_iter())
+    }
+  }
+
+  fn text(nodes: &[usize]) -> String {
+    nodes.iter().map(|n| n.to_string()).collect::<Vec<String>>().join(" ")
+  }
+
+  #[test]
+  fn metadata_sum_and_value_match_the_generated_tree() {
+    fn property(root: Node) -> bool {
+      let mut nodes = Vec::new();
+      root.serialize(&mut nodes);
+      let tree = Tree::parse(&text(&nodes)).unwrap();
+
+      tree.metadata_sum().unwrap() == root.metadata_sum() && tree.root_value().unwrap() == root.value()
+    }
+
+    QuickCheck::new().quickcheck(property as fn(Node) -> bool);
+  }
+}
+
+#[cfg(feature = "day9")]
+mod day9 {
+  use super::*;
+  use quickcheck::QuickCheck;
+  use rust_advent_of_code_2018::day9::{Day9, Game};
+  use rust_advent_of_code_2018::solver::{Context, Puzzle};
+
+  #[derive(Debug, Clone)]
+  struct Players(usize, u32);
+
+  impl Arbitrary for Players {
+    fn arbitrary(g: &mut Gen) -> Players {
+      Players(1 + below(g, 30), 1 + below(g, 3000) as u32)
+    }
+  }
+
+  #[test]
+  fn strategies_agree() {
+    fn property(game: Players) -> bool {
+      let game = Game::new(game.0, game.1);
+      let score = |strategy: &str| Day9.solve(1, &game, &Context::default().with_strategy(Some(strategy.to_string()))).ok();
+
+      score("vec").is_some() && score("vec") == score("linked-list")
+    }
+
+    QuickCheck::new().quickcheck(property as fn(Players) -> bool);
+  }
+}
@@ -0,0 +1,35 @@
+extern crate rust_advent_of_code_2018;
+
+mod common;
+
+use std::fs;
+
+use common::{config, serve};
+use rust_advent_of_code_2018::solver::Answer;
+use rust_advent_of_code_2018::submit::{submit, Ledger, Submission, Verdict};
+
+const TOO_HIGH: &str = "<html><main><article><p>That's not the right answer; your answer is too high.</p></article></main></html>";
+const CORRECT: &str = "<html><main><article><p>That's the right answer! You are one gold star closer.</p></article></main></html>";
+
+#[test]
+fn submissions_are_recorded_and_wrong_answers_never_resent() {
+  let (base_url, server) = serve(vec![(200, TOO_HIGH), (200, CORRECT)]);
+  let config = config("submit", base_url);
+  fs::create_dir_all(&config.data_dir).unwrap();
+  let ledger = format!("{}/ledger.toml", config.data_dir);
+  let submit = |answer: i64| submit(1, 2, &Answer::Number(answer), &config, Some("secret"), &ledger).unwrap();
+
+  assert!(matches!(submit(500), Submission::Answered { verdict: Verdict::TooHigh, .. }));
+  assert!(matches!(submit(500), Submission::Refused(_)));
+  assert!(matches!(submit(600), Submission::Refused(_)));
+  assert!(matches!(submit(448), Submission::Answered { verdict: Verdict::Correct, .. }));
+  assert!(matches!(submit(449), Submission::Refused(_)));
+
+  let requests = server.join().unwrap();
+  assert_eq!(requests.len(), 2);
+  assert_eq!(requests[0].line, "POST /2018/day/1/answer HTTP/1.1");
+  assert_eq!(requests[0].body, "level=2&answer=500");
+
+  let verdicts: Vec<Verdict> = Ledger::load(&ledger).unwrap().attempt.iter().map(|a| a.verdict).collect();
+  assert_eq!(verdicts, vec![Verdict::TooHigh, Verdict::Correct]);
+}